@@ -1,4 +1,14 @@
 pub use crate::api_client::api_client::ApiClient;
-pub use crate::api_client::types::Payload;
+pub use crate::api_client::error::ApiError;
+pub use crate::api_client::handle::MessageHandle;
+pub use crate::api_client::types::{
+    Edited, FileKind, InputFile, InputMedia, InputPollOption, MessageTarget, Payload, SendInvoice,
+    SendPoll,
+};
+#[allow(clippy::module_inception)]
 mod api_client;
+mod cache;
+mod error;
+mod handle;
+pub mod markdown;
 mod types;
@@ -1,15 +1,41 @@
 use std::sync::Arc;
 
-use crate::api_client::types::Payload;
+use crate::api_client::cache::ResponseCache;
+use crate::api_client::error::ApiError;
+use crate::api_client::types::{
+    Edited, FileKind, GetUpdates, InputFile, InputMedia, MessageTarget, Payload, SendInvoice,
+    SendPoll,
+};
+use crate::core::acl::ChatAdminSource;
 use crate::core::bot::Bot as BotConfig;
-use crate::http_client::HttpClient;
-use crate::types::{Bot, Message, Update};
-use futures_core::stream::Stream;
+use crate::http_client::{HttpClient, HttpResponse};
+use crate::types::{
+    Bot, BusinessConnection, ChatAdministratorRights, ChatFullInfo, ChatMember, ChatPermissions,
+    CommandDescription, File, ForumTopic, Media, Message, MessageId, Poll, ReplyMarkup,
+    ShippingOption, StarTransactions, Update,
+};
+use async_trait::async_trait;
+use futures_util::StreamExt;
 use std::collections::HashMap;
+use std::path::Path;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+
+/// How long results of `getChat` and friends are reused.
+const CHAT_CACHE_TTL: Duration = Duration::from_secs(30);
 
 impl<T: HttpClient> ApiClient<T> {
     pub fn new(client: Arc<T>, bot_config: Arc<BotConfig>) -> Self {
-        Self { client, bot_config }
+        Self {
+            client,
+            bot_config,
+            cache: ResponseCache::new(CHAT_CACHE_TTL),
+        }
+    }
+
+    /// The bot this client calls the API for.
+    pub fn bot(&self) -> &Arc<BotConfig> {
+        &self.bot_config
     }
 
     pub fn update_offset(&self, offset: i64) {
@@ -22,13 +48,48 @@ impl<T: HttpClient> ApiClient<T> {
             self.bot_config
                 .offset
                 .store(offset + 1, std::sync::atomic::Ordering::Relaxed);
+            if let Some(metrics) = &self.bot_config.metrics {
+                metrics.set_offset(&self.bot_config.name, offset + 1);
+            }
         }
     }
 
+    /// Format an HTTP client error with the bot token scrubbed out.
+    fn format_error(&self, err: T::Error) -> String {
+        self.bot_config.token.redact(&self.client.format_error(err))
+    }
+
+    /// Record the outcome of an API call in the bot metrics, if enabled.
+    fn observe(
+        bot_config: &BotConfig,
+        method: &str,
+        resp: Option<serde_json::Value>,
+    ) -> Option<serde_json::Value> {
+        if let Some(metrics) = &bot_config.metrics {
+            metrics.record_api_call(&bot_config.name, method);
+            let ok = resp
+                .as_ref()
+                .and_then(|resp| resp.get("ok"))
+                .and_then(|ok| ok.as_bool())
+                .unwrap_or(false);
+            if !ok {
+                metrics.record_api_error(&bot_config.name, method);
+            }
+            let error_code = resp
+                .as_ref()
+                .and_then(|resp| resp.get("error_code"))
+                .and_then(|code| code.as_i64());
+            if error_code == Some(429) {
+                metrics.record_rate_limited(&bot_config.name);
+            }
+        }
+        resp
+    }
+
     /// Parse a JSON response into a Value.
-    fn parse_json(resp: String) -> Option<serde_json::Value> {
-        serde_json::from_str::<serde_json::Value>(&resp)
-            .map_err(|e| println!("Error parsing response: {:#?}", e))
+    fn parse_json(resp: HttpResponse) -> Option<serde_json::Value> {
+        serde_json::from_slice::<serde_json::Value>(&resp.body)
+            .map_err(|e| println!("Error parsing response (HTTP {}): {:#?}", resp.status, e))
             .ok()
     }
 
@@ -42,24 +103,286 @@ impl<T: HttpClient> ApiClient<T> {
             .ok()
     }
 
+    /// Call an API method with a JSON payload and parse its result.
+    async fn call<P, RT>(&self, method: &str, payload: &P) -> Option<RT>
+    where
+        P: serde::Serialize,
+        RT: serde::de::DeserializeOwned,
+    {
+        self.call_value(method, payload)
+            .await
+            .and_then(Self::parse_response)
+    }
+
+    /// Like `call`, but reuses a recent result for the same request about the chat.
+    async fn cached_call<RT>(
+        &self,
+        chat_id: i64,
+        method: &str,
+        payload: serde_json::Value,
+    ) -> Option<RT>
+    where
+        RT: serde::de::DeserializeOwned,
+    {
+        let key = format!("{method}:{payload}");
+        if let Some(result) = self.cache.get(chat_id, &key) {
+            return Self::parse_response(result);
+        }
+        let result = self.call_value(method, &payload).await?;
+        self.cache.insert(chat_id, &key, result.clone());
+        Self::parse_response(result)
+    }
+
+    /// Drops cached information about the chat.
+    pub fn invalidate_chat_cache(&self, chat_id: i64) {
+        self.cache.invalidate_chat(chat_id);
+    }
+
+    /// Call an API method with a JSON payload and return the raw result.
+    async fn call_value<P>(&self, method: &str, payload: &P) -> Option<serde_json::Value>
+    where
+        P: serde::Serialize,
+    {
+        let url = self.bot_config.url(method);
+        let body = serde_json::to_string(payload)
+            .map_err(|err| println!("Error serializing payload: {:#?}", err))
+            .ok()?;
+        let resp = self
+            .client
+            .post(&url, body)
+            .await
+            .map_err(|err| println!("Err on request: {:#?}", self.format_error(err)))
+            .ok()
+            .and_then(Self::parse_json);
+        let resp = Self::observe(&self.bot_config, method, resp)?;
+        if !resp.get("ok").and_then(|ok| ok.as_bool()).unwrap_or(false) {
+            println!(
+                "{method} failed: {}",
+                resp.get("description")
+                    .and_then(|d| d.as_str())
+                    .unwrap_or_default()
+            );
+            return None;
+        }
+        resp.get("result").cloned()
+    }
+
     /// Send a message to the chat.
     pub async fn send_message(&self, chat_id: i64, text: String) -> Option<Message> {
-        let url = self.bot_config.url("sendMessage");
-        let payload = Payload::new(chat_id, text, String::from("MarkdownV2"));
+        self.send_message_to_topic(chat_id, None, text).await
+    }
+
+    /// Replies in the chat and forum topic the message was sent in. Replies
+    /// to business messages are sent on behalf of the business account.
+    pub async fn reply(&self, message: &Message, text: String) -> Option<Message> {
+        self.reply_with_markup(message, text, None).await
+    }
+
+    /// Like `reply`, showing a reply keyboard, removing it or forcing a reply.
+    pub async fn reply_with_markup(
+        &self,
+        message: &Message,
+        text: String,
+        reply_markup: Option<ReplyMarkup>,
+    ) -> Option<Message> {
+        let payload = Payload::new(message.chat.id(), text, String::from("MarkdownV2"))
+            .in_topic(message.reply_thread_id())
+            .via_business(message.business_connection_id.clone())
+            .with_markup(reply_markup);
+        let reply = self.send_payload(payload).await?;
+        self.bot_config.replies.record(message, &reply);
+        Some(reply)
+    }
+
+    /// Sends a message with a reply keyboard, or removes the keyboard.
+    pub async fn send_message_with_markup(
+        &self,
+        chat_id: i64,
+        text: String,
+        reply_markup: ReplyMarkup,
+    ) -> Option<Message> {
+        let payload =
+            Payload::new(chat_id, text, String::from("MarkdownV2")).with_markup(Some(reply_markup));
+        self.send_payload(payload).await
+    }
+
+    pub async fn send_message_to_topic(
+        &self,
+        chat_id: i64,
+        message_thread_id: Option<i64>,
+        text: String,
+    ) -> Option<Message> {
+        let payload =
+            Payload::new(chat_id, text, String::from("MarkdownV2")).in_topic(message_thread_id);
+        self.send_payload(payload).await
+    }
+
+    async fn send_payload(&self, payload: Payload) -> Option<Message> {
+        const METHOD: &str = "sendMessage";
+        let url = self.bot_config.url(METHOD);
         let body = serde_json::to_string(&payload)
             .map_err(|err| println!("Error serializing payload: {:#?}", err))
             .ok()?;
         println!("Sending message: {body} to chat {}", payload.chat_id);
-        self.client
+        let resp = self
+            .client
             .post(&url, body)
             .await
-            .map_err(|err| println!("Err on request: {:#?}", self.client.format_error(err)))
+            .map_err(|err| println!("Err on request: {:#?}", self.format_error(err)))
+            .ok()
+            .and_then(Self::parse_json);
+        Self::observe(&self.bot_config, METHOD, resp)?
+            .get("result")
+            .and_then(|x| Self::parse_response(x.to_owned()))?
+    }
+
+    /// Checks the size of a file before uploading it, so oversized files
+    /// fail early instead of after a long upload.
+    fn fits_upload_limit(&self, path: &str) -> bool {
+        let limit = self.bot_config.upload_limit();
+        match std::fs::metadata(path) {
+            Ok(metadata) if metadata.len() > limit => {
+                println!(
+                    "Error: {path} is {} bytes, the upload limit is {limit} bytes",
+                    metadata.len()
+                );
+                false
+            }
+            Ok(_) => true,
+            Err(err) => {
+                println!("Error: cannot read {path}: {err}");
+                false
+            }
+        }
+    }
+
+    /// Sends a file by ID, URL, `file://` URI or upload.
+    pub async fn send_file(
+        &self,
+        chat_id: i64,
+        kind: FileKind,
+        file: &InputFile,
+        caption: Option<String>,
+    ) -> Option<Message> {
+        if let InputFile::Local(_) = file {
+            if !self.bot_config.local_mode {
+                println!("Error: file:// URIs need a local Bot API server");
+                return None;
+            }
+        }
+        let InputFile::Upload(path) = file else {
+            let mut payload = serde_json::json!({ "chat_id": chat_id });
+            payload[kind.field()] = file.as_param()?.into();
+            if let Some(caption) = caption {
+                payload["caption"] = caption.into();
+            }
+            return self.call(kind.send_method(), &payload).await;
+        };
+        let path = path.to_str()?;
+        if !self.fits_upload_limit(path) {
+            return None;
+        }
+        let file_name = std::path::Path::new(path).file_name()?.to_str()?;
+        let mut body = HashMap::from([
+            ("chat_id".to_string(), chat_id.to_string()),
+            (kind.field().to_string(), format!("attach://{file_name}")),
+        ]);
+        if let Some(caption) = caption {
+            body.insert("caption".to_string(), caption);
+        }
+        let url = self.bot_config.url(kind.send_method());
+        let resp = self
+            .client
+            .post_multipart(&url, body, Some(path))
+            .await
+            .map_err(|err| println!("Err on request: {:#?}", self.format_error(err)))
             .ok()
-            .and_then(Self::parse_json)?
+            .and_then(Self::parse_json);
+        Self::observe(&self.bot_config, kind.send_method(), resp)?
             .get("result")
             .and_then(|x| Self::parse_response(x.to_owned()))?
     }
 
+    /// Prepares a file for downloading. With a local Bot API server the
+    /// `file_path` is an absolute path on the server's disk.
+    pub async fn get_file(&self, file_id: &str) -> Option<File> {
+        let payload = serde_json::json!({ "file_id": file_id });
+        self.call("getFile", &payload).await
+    }
+
+    /// Reads the content of a file: straight from disk in local mode,
+    /// otherwise downloaded from the cloud API.
+    pub async fn download_file(&self, file: &File) -> Option<Vec<u8>> {
+        let file_path = file.file_path.as_deref()?;
+        if self.bot_config.local_mode && Path::new(file_path).is_absolute() {
+            return tokio::fs::read(file_path)
+                .await
+                .map_err(|err| println!("Error reading {file_path}: {err}"))
+                .ok();
+        }
+        let resp = self
+            .client
+            .get(&self.bot_config.file_url(file_path))
+            .await
+            .map_err(|err| println!("Err on request: {:#?}", self.format_error(err)))
+            .ok()?;
+        if !resp.is_success() {
+            println!("Downloading {} failed: HTTP {}", file.file_id, resp.status);
+            return None;
+        }
+        Some(resp.body)
+    }
+
+    /// Saves a file to `destination` without holding it in memory and
+    /// returns its size.
+    pub async fn save_file(&self, file: &File, destination: &Path) -> Option<u64> {
+        let file_path = file.file_path.as_deref()?;
+        if self.bot_config.local_mode && Path::new(file_path).is_absolute() {
+            return tokio::fs::copy(file_path, destination)
+                .await
+                .map_err(|err| println!("Error copying {file_path}: {err}"))
+                .ok();
+        }
+        let mut resp = self
+            .client
+            .get_stream(&self.bot_config.file_url(file_path))
+            .await
+            .map_err(|err| println!("Err on request: {:#?}", self.format_error(err)))
+            .ok()?;
+        if !resp.is_success() {
+            println!("Downloading {} failed: HTTP {}", file.file_id, resp.status);
+            return None;
+        }
+        let mut output = tokio::fs::File::create(destination)
+            .await
+            .map_err(|err| println!("Error creating {}: {err}", destination.display()))
+            .ok()?;
+        let mut size = 0;
+        while let Some(chunk) = resp.body.next().await {
+            let chunk = chunk
+                .map_err(|err| println!("Err on request: {:#?}", self.format_error(err)))
+                .ok()?;
+            output
+                .write_all(&chunk)
+                .await
+                .map_err(|err| println!("Error writing {}: {err}", destination.display()))
+                .ok()?;
+            size += chunk.len() as u64;
+        }
+        Some(size)
+    }
+
+    /// Logs out from the cloud Bot API server. Call it before switching the
+    /// bot to a local server; the bot can't log in to the cloud for 10 minutes.
+    pub async fn log_out(&self) -> Option<bool> {
+        self.call("logOut", &serde_json::json!({})).await
+    }
+
+    /// Closes the bot instance before moving it from one local server to another.
+    pub async fn close(&self) -> Option<bool> {
+        self.call("close", &serde_json::json!({})).await
+    }
+
     /// Send video to the chat.
     pub async fn send_video(
         &self,
@@ -67,75 +390,696 @@ impl<T: HttpClient> ApiClient<T> {
         attach_name: &str,
         video_path: &str,
     ) -> Option<Message> {
-        let url = self.bot_config.url("sendVideo");
+        const METHOD: &str = "sendVideo";
+        if !self.fits_upload_limit(video_path) {
+            return None;
+        }
+        let url = self.bot_config.url(METHOD);
         let body = HashMap::from([
             ("chat_id".to_string(), chat_id.to_string()),
             ("video".to_string(), format!("attach://{}", attach_name)),
         ]);
         println!("Sending message: {body:#?} to chat {}", chat_id);
-        self.client
+        let resp = self
+            .client
             .post_multipart(&url, body, Some(video_path))
             .await
-            .map_err(|err| println!("Err on request: {:#?}", self.client.format_error(err)))
+            .map_err(|err| println!("Err on request: {:#?}", self.format_error(err)))
             .ok()
-            .and_then(Self::parse_json)?
+            .and_then(Self::parse_json);
+        Self::observe(&self.bot_config, METHOD, resp)?
             .get("result")
             .and_then(|x| Self::parse_response(x.to_owned()))?
     }
 
-    async fn get_updates(client: Arc<T>, url: String) -> Option<Vec<Update>> {
-        println!("Calling: {url}");
-        let resp = client
-            .get(&url)
-            .await
-            .map_err(|err| println!("Err on request: {:#?}", client.format_error(err)))
-            .ok()
-            .and_then(Self::parse_json)?;
-        if !resp.get("ok").and_then(|ok| ok.as_bool()).unwrap_or(false) {
+    /// Send media that is already on Telegram servers by its file ID,
+    /// e.g. to echo back what a user sent.
+    pub async fn send_media(
+        &self,
+        chat_id: i64,
+        message_thread_id: Option<i64>,
+        media: &Media<'_>,
+        caption: Option<String>,
+    ) -> Option<Message> {
+        let mut payload = serde_json::json!({ "chat_id": chat_id });
+        if let Some(message_thread_id) = message_thread_id {
+            payload["message_thread_id"] = message_thread_id.into();
+        }
+        payload[media.field()] = media.file_id()?.into();
+        if let Some(caption) = caption.filter(|_| media.supports_caption()) {
+            payload["caption"] = caption.into();
+        }
+        self.call(media.send_method(), &payload).await
+    }
+
+    /// Sends media to the chat, forum topic and business account the message came from.
+    pub async fn reply_media(
+        &self,
+        message: &Message,
+        media: &Media<'_>,
+        caption: Option<String>,
+    ) -> Option<Message> {
+        let mut payload = serde_json::json!({ "chat_id": message.chat.id() });
+        if let Some(message_thread_id) = message.reply_thread_id() {
+            payload["message_thread_id"] = message_thread_id.into();
+        }
+        if let Some(business_connection_id) = &message.business_connection_id {
+            payload["business_connection_id"] = business_connection_id.as_str().into();
+        }
+        payload[media.field()] = media.file_id()?.into();
+        if let Some(caption) = caption.filter(|_| media.supports_caption()) {
+            payload["caption"] = caption.into();
+        }
+        let reply: Message = self.call(media.send_method(), &payload).await?;
+        self.bot_config.replies.record(message, &reply);
+        Some(reply)
+    }
+
+    /// Adds the fields of `target` to an object payload.
+    fn with_target(
+        target: &MessageTarget,
+        mut payload: serde_json::Value,
+    ) -> Option<serde_json::Value> {
+        let serde_json::Value::Object(fields) = serde_json::to_value(target).ok()? else {
             return None;
+        };
+        payload.as_object_mut()?.extend(fields);
+        Some(payload)
+    }
+
+    pub async fn edit_message_text(&self, target: &MessageTarget, text: String) -> Option<Edited> {
+        let payload = serde_json::json!({ "text": text, "parse_mode": "MarkdownV2" });
+        self.call("editMessageText", &Self::with_target(target, payload)?)
+            .await
+    }
+
+    pub async fn edit_message_caption(
+        &self,
+        target: &MessageTarget,
+        caption: Option<String>,
+    ) -> Option<Edited> {
+        let payload = serde_json::json!({ "caption": caption, "parse_mode": "MarkdownV2" });
+        self.call("editMessageCaption", &Self::with_target(target, payload)?)
+            .await
+    }
+
+    pub async fn edit_message_media(
+        &self,
+        target: &MessageTarget,
+        media: &InputMedia,
+    ) -> Option<Edited> {
+        let payload = serde_json::json!({ "media": media });
+        self.call("editMessageMedia", &Self::with_target(target, payload)?)
+            .await
+    }
+
+    /// Replaces the inline keyboard of a message, or removes it if `None`.
+    pub async fn edit_message_reply_markup(
+        &self,
+        target: &MessageTarget,
+        reply_markup: Option<&serde_json::Value>,
+    ) -> Option<Edited> {
+        let mut payload = serde_json::json!({});
+        if let Some(reply_markup) = reply_markup {
+            payload["reply_markup"] = reply_markup.clone();
         }
-        resp.clone()
-            .get("result")
-            .and_then(|result| Self::parse_response::<Vec<Update>>(result.to_owned()))
+        self.call(
+            "editMessageReplyMarkup",
+            &Self::with_target(target, payload)?,
+        )
+        .await
     }
 
-    /// Yield updates from the API.
-    pub async fn yield_updates(&self) -> impl Stream<Item = Update> {
-        let url = format!(
-            "{}?offset={}&timeout={}",
-            self.bot_config.url("getUpdates"),
-            self.bot_config
+    pub async fn delete_message(&self, chat_id: i64, message_id: i64) -> Option<bool> {
+        let payload = serde_json::json!({ "chat_id": chat_id, "message_id": message_id });
+        self.call("deleteMessage", &payload).await
+    }
+
+    /// Deletes up to 100 messages of a chat at once.
+    pub async fn delete_messages(&self, chat_id: i64, message_ids: &[i64]) -> Option<bool> {
+        let payload = serde_json::json!({ "chat_id": chat_id, "message_ids": message_ids });
+        self.call("deleteMessages", &payload).await
+    }
+
+    pub async fn forward_message(
+        &self,
+        chat_id: i64,
+        from_chat_id: i64,
+        message_id: i64,
+    ) -> Option<Message> {
+        let payload = serde_json::json!({
+            "chat_id": chat_id,
+            "from_chat_id": from_chat_id,
+            "message_id": message_id,
+        });
+        self.call("forwardMessage", &payload).await
+    }
+
+    /// Forwards up to 100 messages, keeping albums grouped.
+    pub async fn forward_messages(
+        &self,
+        chat_id: i64,
+        from_chat_id: i64,
+        message_ids: &[i64],
+    ) -> Option<Vec<MessageId>> {
+        let payload = serde_json::json!({
+            "chat_id": chat_id,
+            "from_chat_id": from_chat_id,
+            "message_ids": message_ids,
+        });
+        self.call("forwardMessages", &payload).await
+    }
+
+    /// Copies a message without a link to the original. `caption` replaces
+    /// the caption of media messages.
+    pub async fn copy_message(
+        &self,
+        chat_id: i64,
+        from_chat_id: i64,
+        message_id: i64,
+        caption: Option<String>,
+    ) -> Option<MessageId> {
+        let mut payload = serde_json::json!({
+            "chat_id": chat_id,
+            "from_chat_id": from_chat_id,
+            "message_id": message_id,
+        });
+        if let Some(caption) = caption {
+            payload["caption"] = caption.into();
+            payload["parse_mode"] = "MarkdownV2".into();
+        }
+        self.call("copyMessage", &payload).await
+    }
+
+    pub async fn copy_messages(
+        &self,
+        chat_id: i64,
+        from_chat_id: i64,
+        message_ids: &[i64],
+    ) -> Option<Vec<MessageId>> {
+        let payload = serde_json::json!({
+            "chat_id": chat_id,
+            "from_chat_id": from_chat_id,
+            "message_ids": message_ids,
+        });
+        self.call("copyMessages", &payload).await
+    }
+
+    pub async fn send_poll(&self, chat_id: i64, poll: &SendPoll) -> Option<Message> {
+        let mut payload = serde_json::to_value(poll).ok()?;
+        payload["chat_id"] = chat_id.into();
+        self.call("sendPoll", &payload).await
+    }
+
+    /// Stops a poll sent by the bot and returns its final state.
+    pub async fn stop_poll(&self, chat_id: i64, message_id: i64) -> Option<Poll> {
+        let payload = serde_json::json!({ "chat_id": chat_id, "message_id": message_id });
+        self.call("stopPoll", &payload).await
+    }
+
+    pub async fn send_invoice(&self, chat_id: i64, invoice: &SendInvoice) -> Option<Message> {
+        let mut payload = serde_json::to_value(invoice).ok()?;
+        payload["chat_id"] = chat_id.into();
+        self.call("sendInvoice", &payload).await
+    }
+
+    /// Creates a link to an invoice that can be shared anywhere.
+    pub async fn create_invoice_link(&self, invoice: &SendInvoice) -> Option<String> {
+        let mut payload = serde_json::to_value(invoice).ok()?;
+        payload.as_object_mut()?.remove("message_thread_id");
+        self.call("createInvoiceLink", &payload).await
+    }
+
+    /// Answers a shipping query with the available options, or an error
+    /// message shown to the user if delivery is impossible.
+    pub async fn answer_shipping_query(
+        &self,
+        shipping_query_id: &str,
+        result: Result<Vec<ShippingOption>, String>,
+    ) -> Option<bool> {
+        let payload = match result {
+            Ok(shipping_options) => serde_json::json!({
+                "shipping_query_id": shipping_query_id,
+                "ok": true,
+                "shipping_options": shipping_options,
+            }),
+            Err(error_message) => serde_json::json!({
+                "shipping_query_id": shipping_query_id,
+                "ok": false,
+                "error_message": error_message,
+            }),
+        };
+        self.call("answerShippingQuery", &payload).await
+    }
+
+    /// Confirms a pre-checkout query, or rejects it with a message shown to the user.
+    pub async fn answer_pre_checkout_query(
+        &self,
+        pre_checkout_query_id: &str,
+        result: Result<(), String>,
+    ) -> Option<bool> {
+        let payload = match result {
+            Ok(()) => serde_json::json!({
+                "pre_checkout_query_id": pre_checkout_query_id,
+                "ok": true,
+            }),
+            Err(error_message) => serde_json::json!({
+                "pre_checkout_query_id": pre_checkout_query_id,
+                "ok": false,
+                "error_message": error_message,
+            }),
+        };
+        self.call("answerPreCheckoutQuery", &payload).await
+    }
+
+    pub async fn refund_star_payment(
+        &self,
+        user_id: i64,
+        telegram_payment_charge_id: &str,
+    ) -> Option<bool> {
+        let payload = serde_json::json!({
+            "user_id": user_id,
+            "telegram_payment_charge_id": telegram_payment_charge_id,
+        });
+        self.call("refundStarPayment", &payload).await
+    }
+
+    /// Returns up to `limit` (1-100) Star transactions of the bot, newest first.
+    pub async fn get_star_transactions(&self, offset: i64, limit: i64) -> Option<StarTransactions> {
+        let payload = serde_json::json!({ "offset": offset, "limit": limit });
+        self.call("getStarTransactions", &payload).await
+    }
+
+    /// Creates a topic in a forum supergroup. `icon_color` is one of the
+    /// colors allowed by Telegram, e.g. `0x6FB9F0`.
+    pub async fn create_forum_topic(
+        &self,
+        chat_id: i64,
+        name: &str,
+        icon_color: Option<i64>,
+        icon_custom_emoji_id: Option<&str>,
+    ) -> Option<ForumTopic> {
+        let mut payload = serde_json::json!({ "chat_id": chat_id, "name": name });
+        if let Some(icon_color) = icon_color {
+            payload["icon_color"] = icon_color.into();
+        }
+        if let Some(icon_custom_emoji_id) = icon_custom_emoji_id {
+            payload["icon_custom_emoji_id"] = icon_custom_emoji_id.into();
+        }
+        self.call("createForumTopic", &payload).await
+    }
+
+    /// Changes the name and icon of a topic. Unset values are kept,
+    /// an empty `icon_custom_emoji_id` removes the icon.
+    pub async fn edit_forum_topic(
+        &self,
+        chat_id: i64,
+        message_thread_id: i64,
+        name: Option<&str>,
+        icon_custom_emoji_id: Option<&str>,
+    ) -> Option<bool> {
+        let mut payload =
+            serde_json::json!({ "chat_id": chat_id, "message_thread_id": message_thread_id });
+        if let Some(name) = name {
+            payload["name"] = name.into();
+        }
+        if let Some(icon_custom_emoji_id) = icon_custom_emoji_id {
+            payload["icon_custom_emoji_id"] = icon_custom_emoji_id.into();
+        }
+        self.call("editForumTopic", &payload).await
+    }
+
+    async fn topic_call(&self, method: &str, chat_id: i64, message_thread_id: i64) -> Option<bool> {
+        let payload =
+            serde_json::json!({ "chat_id": chat_id, "message_thread_id": message_thread_id });
+        self.call(method, &payload).await
+    }
+
+    pub async fn close_forum_topic(&self, chat_id: i64, message_thread_id: i64) -> Option<bool> {
+        self.topic_call("closeForumTopic", chat_id, message_thread_id)
+            .await
+    }
+
+    pub async fn reopen_forum_topic(&self, chat_id: i64, message_thread_id: i64) -> Option<bool> {
+        self.topic_call("reopenForumTopic", chat_id, message_thread_id)
+            .await
+    }
+
+    /// Deletes a topic along with all its messages.
+    pub async fn delete_forum_topic(&self, chat_id: i64, message_thread_id: i64) -> Option<bool> {
+        self.topic_call("deleteForumTopic", chat_id, message_thread_id)
+            .await
+    }
+
+    pub async fn unpin_all_forum_topic_messages(
+        &self,
+        chat_id: i64,
+        message_thread_id: i64,
+    ) -> Option<bool> {
+        self.topic_call("unpinAllForumTopicMessages", chat_id, message_thread_id)
+            .await
+    }
+
+    pub async fn get_business_connection(
+        &self,
+        business_connection_id: &str,
+    ) -> Option<BusinessConnection> {
+        let payload = serde_json::json!({ "business_connection_id": business_connection_id });
+        self.call("getBusinessConnection", &payload).await
+    }
+
+    /// Marks an incoming message of a business account as read.
+    pub async fn read_business_message(
+        &self,
+        business_connection_id: &str,
+        chat_id: i64,
+        message_id: i64,
+    ) -> Option<bool> {
+        let payload = serde_json::json!({
+            "business_connection_id": business_connection_id,
+            "chat_id": chat_id,
+            "message_id": message_id,
+        });
+        self.call("readBusinessMessage", &payload).await
+    }
+
+    /// Deletes up to 100 messages of one chat on behalf of a business account.
+    pub async fn delete_business_messages(
+        &self,
+        business_connection_id: &str,
+        message_ids: &[i64],
+    ) -> Option<bool> {
+        let payload = serde_json::json!({
+            "business_connection_id": business_connection_id,
+            "message_ids": message_ids,
+        });
+        self.call("deleteBusinessMessages", &payload).await
+    }
+
+    pub async fn set_business_account_name(
+        &self,
+        business_connection_id: &str,
+        first_name: &str,
+        last_name: Option<&str>,
+    ) -> Option<bool> {
+        let mut payload = serde_json::json!({
+            "business_connection_id": business_connection_id,
+            "first_name": first_name,
+        });
+        if let Some(last_name) = last_name {
+            payload["last_name"] = last_name.into();
+        }
+        self.call("setBusinessAccountName", &payload).await
+    }
+
+    /// Changes the username of a business account, `None` removes it.
+    pub async fn set_business_account_username(
+        &self,
+        business_connection_id: &str,
+        username: Option<&str>,
+    ) -> Option<bool> {
+        let mut payload = serde_json::json!({ "business_connection_id": business_connection_id });
+        if let Some(username) = username {
+            payload["username"] = username.into();
+        }
+        self.call("setBusinessAccountUsername", &payload).await
+    }
+
+    /// Changes the bio of a business account, `None` removes it.
+    pub async fn set_business_account_bio(
+        &self,
+        business_connection_id: &str,
+        bio: Option<&str>,
+    ) -> Option<bool> {
+        let mut payload = serde_json::json!({ "business_connection_id": business_connection_id });
+        if let Some(bio) = bio {
+            payload["bio"] = bio.into();
+        }
+        self.call("setBusinessAccountBio", &payload).await
+    }
+
+    /// Makes Telegram push updates to `url`. The `secret_token` is sent back
+    /// in the `X-Telegram-Bot-Api-Secret-Token` header of every request.
+    pub async fn set_webhook(
+        &self,
+        url: &str,
+        secret_token: Option<&str>,
+        allowed_updates: Option<Vec<String>>,
+    ) -> Option<bool> {
+        let mut payload = serde_json::json!({ "url": url });
+        if let Some(secret_token) = secret_token {
+            payload["secret_token"] = secret_token.into();
+        }
+        if let Some(allowed_updates) = allowed_updates {
+            payload["allowed_updates"] = allowed_updates.into();
+        }
+        self.call("setWebhook", &payload).await
+    }
+
+    /// Switches back to `getUpdates`.
+    pub async fn delete_webhook(&self, drop_pending_updates: bool) -> Option<bool> {
+        let payload = serde_json::json!({ "drop_pending_updates": drop_pending_updates });
+        self.call("deleteWebhook", &payload).await
+    }
+
+    /// Long-polls for updates after the current offset. Errors are
+    /// classified, so the caller can decide whether and when to retry.
+    pub async fn get_updates(
+        &self,
+        limit: Option<u32>,
+        allowed_updates: Option<Vec<String>>,
+    ) -> Result<Vec<Update>, ApiError> {
+        const METHOD: &str = "getUpdates";
+        let params = GetUpdates {
+            offset: self
+                .bot_config
                 .offset
                 .load(std::sync::atomic::Ordering::Relaxed),
-            self.bot_config.polling_timeout
-        );
-        let client = self.client.clone();
-        async_stream::stream! {
-            match Self::get_updates(client, url).await {
-                Some(updates) => {
-                    for update in updates {
-                        yield update;
-                    }
-                }
-                None => {
-                    // Handle the case when no updates are received
-                    // For example, you can log a message or retry the request
-                    println!("No updates received");
-                }
+            timeout: self.bot_config.polling_timeout,
+            limit,
+            allowed_updates,
+        };
+        let url = self.bot_config.url(METHOD);
+        let body = serde_json::to_string(&params)
+            .map_err(|err| ApiError::InvalidResponse(err.to_string()))?;
+        println!("Calling: {} {body}", self.bot_config.display_url(METHOD));
+        let http_resp = self
+            .client
+            .long_poll(
+                &url,
+                body,
+                std::time::Duration::from_secs(self.bot_config.polling_timeout.max(0) as u64),
+            )
+            .await
+            .map_err(|err| ApiError::Transport(self.format_error(err)))?;
+        // Proxies and load balancers answer with their own error pages.
+        let resp = serde_json::from_slice::<serde_json::Value>(&http_resp.body)
+            .map_err(|_| ApiError::from_status(&http_resp));
+        let resp = Self::observe(&self.bot_config, METHOD, resp.clone().ok()).ok_or_else(|| {
+            resp.err()
+                .unwrap_or_else(|| ApiError::InvalidResponse(String::from("empty response")))
+        })?;
+        if !resp.get("ok").and_then(|ok| ok.as_bool()).unwrap_or(false) {
+            return Err(ApiError::from_response(&resp));
+        }
+        let updates: Vec<Update> = resp
+            .get("result")
+            .cloned()
+            .and_then(Self::parse_response)
+            .ok_or_else(|| ApiError::InvalidResponse(String::from("malformed updates")))?;
+        if let Some(metrics) = &self.bot_config.metrics {
+            for update in &updates {
+                metrics.record_update(&self.bot_config.name, update.kind());
             }
         }
+        Ok(updates)
+    }
+
+    /// Get up-to-date information about the chat. Cached for a short time.
+    pub async fn get_chat(&self, chat_id: i64) -> Option<ChatFullInfo> {
+        self.cached_call(
+            chat_id,
+            "getChat",
+            serde_json::json!({ "chat_id": chat_id }),
+        )
+        .await
+    }
+
+    /// Get the number of members in the chat. Cached for a short time.
+    pub async fn get_chat_member_count(&self, chat_id: i64) -> Option<i64> {
+        self.cached_call(
+            chat_id,
+            "getChatMemberCount",
+            serde_json::json!({ "chat_id": chat_id }),
+        )
+        .await
+    }
+
+    /// Get information about a member of a chat. Cached for a short time.
+    pub async fn get_chat_member(&self, chat_id: i64, user_id: i64) -> Option<ChatMember> {
+        self.cached_call(
+            chat_id,
+            "getChatMember",
+            serde_json::json!({ "chat_id": chat_id, "user_id": user_id }),
+        )
+        .await
+    }
+
+    /// Get the administrators of the chat, bots excluded. Cached for a short time.
+    pub async fn get_chat_administrators(&self, chat_id: i64) -> Option<Vec<ChatMember>> {
+        self.cached_call(
+            chat_id,
+            "getChatAdministrators",
+            serde_json::json!({ "chat_id": chat_id }),
+        )
+        .await
+    }
+
+    /// Calls a method that changes members of a chat and drops the cached
+    /// chat info, since member lookups are stale afterwards.
+    async fn moderate(
+        &self,
+        chat_id: i64,
+        method: &str,
+        payload: serde_json::Value,
+    ) -> Option<bool> {
+        let result = self.call(method, &payload).await;
+        self.invalidate_chat_cache(chat_id);
+        result
+    }
+
+    /// Bans a user until `until_date` (Unix time), or forever if not set.
+    pub async fn ban_chat_member(
+        &self,
+        chat_id: i64,
+        user_id: i64,
+        until_date: Option<i64>,
+        revoke_messages: bool,
+    ) -> Option<bool> {
+        let mut payload = serde_json::json!({
+            "chat_id": chat_id,
+            "user_id": user_id,
+            "revoke_messages": revoke_messages,
+        });
+        if let Some(until_date) = until_date {
+            payload["until_date"] = until_date.into();
+        }
+        self.moderate(chat_id, "banChatMember", payload).await
+    }
+
+    /// Unbans a user. With `only_if_banned` a present member is not kicked.
+    pub async fn unban_chat_member(
+        &self,
+        chat_id: i64,
+        user_id: i64,
+        only_if_banned: bool,
+    ) -> Option<bool> {
+        let payload = serde_json::json!({
+            "chat_id": chat_id,
+            "user_id": user_id,
+            "only_if_banned": only_if_banned,
+        });
+        self.moderate(chat_id, "unbanChatMember", payload).await
+    }
+
+    /// Restricts a user until `until_date` (Unix time), or forever if not set.
+    /// Pass `ChatPermissions::all()` to lift restrictions.
+    pub async fn restrict_chat_member(
+        &self,
+        chat_id: i64,
+        user_id: i64,
+        permissions: &ChatPermissions,
+        until_date: Option<i64>,
+    ) -> Option<bool> {
+        let mut payload = serde_json::json!({
+            "chat_id": chat_id,
+            "user_id": user_id,
+            "permissions": permissions,
+            "use_independent_chat_permissions": true,
+        });
+        if let Some(until_date) = until_date {
+            payload["until_date"] = until_date.into();
+        }
+        self.moderate(chat_id, "restrictChatMember", payload).await
+    }
+
+    /// Promotes or demotes a user. `ChatAdministratorRights::default()` demotes.
+    pub async fn promote_chat_member(
+        &self,
+        chat_id: i64,
+        user_id: i64,
+        rights: &ChatAdministratorRights,
+    ) -> Option<bool> {
+        let mut payload = serde_json::to_value(rights).ok()?;
+        payload["chat_id"] = chat_id.into();
+        payload["user_id"] = user_id.into();
+        self.moderate(chat_id, "promoteChatMember", payload).await
+    }
+
+    pub async fn set_chat_administrator_custom_title(
+        &self,
+        chat_id: i64,
+        user_id: i64,
+        custom_title: &str,
+    ) -> Option<bool> {
+        let payload = serde_json::json!({
+            "chat_id": chat_id,
+            "user_id": user_id,
+            "custom_title": custom_title,
+        });
+        self.moderate(chat_id, "setChatAdministratorCustomTitle", payload)
+            .await
+    }
+
+    /// Bans a channel chat from posting on behalf of the channel.
+    pub async fn ban_chat_sender_chat(&self, chat_id: i64, sender_chat_id: i64) -> Option<bool> {
+        let payload = serde_json::json!({ "chat_id": chat_id, "sender_chat_id": sender_chat_id });
+        self.moderate(chat_id, "banChatSenderChat", payload).await
+    }
+
+    pub async fn unban_chat_sender_chat(&self, chat_id: i64, sender_chat_id: i64) -> Option<bool> {
+        let payload = serde_json::json!({ "chat_id": chat_id, "sender_chat_id": sender_chat_id });
+        self.moderate(chat_id, "unbanChatSenderChat", payload).await
+    }
+
+    pub async fn approve_chat_join_request(&self, chat_id: i64, user_id: i64) -> Option<bool> {
+        let payload = serde_json::json!({ "chat_id": chat_id, "user_id": user_id });
+        self.moderate(chat_id, "approveChatJoinRequest", payload)
+            .await
+    }
+
+    pub async fn decline_chat_join_request(&self, chat_id: i64, user_id: i64) -> Option<bool> {
+        let payload = serde_json::json!({ "chat_id": chat_id, "user_id": user_id });
+        self.moderate(chat_id, "declineChatJoinRequest", payload)
+            .await
+    }
+
+    /// Set the command menu shown to users with the given language,
+    /// or to everyone without a localized menu if `language_code` is `None`.
+    pub async fn set_my_commands(
+        &self,
+        commands: &[CommandDescription],
+        language_code: Option<&str>,
+    ) -> Option<bool> {
+        let mut payload = serde_json::json!({ "commands": commands });
+        if let Some(language_code) = language_code {
+            payload["language_code"] = language_code.into();
+        }
+        self.call("setMyCommands", &payload).await
     }
 
     /// Get information about the bot itself.
     pub async fn get_me(&self) -> Option<Bot> {
-        let url = self.bot_config.url("getMe");
+        const METHOD: &str = "getMe";
+        let url = self.bot_config.url(METHOD);
         let payload = self
             .client
             .get(&url)
             .await
-            .map_err(|err| println!("Err on request: {:#?}", self.client.format_error(err)))
+            .map_err(|err| println!("Err on request: {:#?}", self.format_error(err)))
             .ok()
-            .and_then(Self::parse_json)?;
+            .and_then(Self::parse_json);
+        let payload = Self::observe(&self.bot_config, METHOD, payload)?;
         if payload
             .get("ok")
             .and_then(|ok| ok.as_bool())
@@ -147,11 +1091,24 @@ impl<T: HttpClient> ApiClient<T> {
                     .ok()
             });
         }
-        return None;
+        None
+    }
+}
+
+#[async_trait]
+impl<T> ChatAdminSource for ApiClient<T>
+where
+    T: HttpClient + Send + Sync,
+{
+    async fn is_chat_admin(&self, chat_id: i64, user_id: i64) -> bool {
+        self.get_chat_member(chat_id, user_id)
+            .await
+            .is_some_and(|member| member.is_admin())
     }
 }
 
 pub struct ApiClient<T: HttpClient> {
     client: Arc<T>,
     bot_config: Arc<BotConfig>,
+    cache: ResponseCache,
 }
@@ -0,0 +1,39 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Short-lived cache of API results about chats, keyed by chat ID and request.
+/// Keeps repeated lookups such as admin checks from hitting the API on every command.
+pub struct ResponseCache {
+    ttl: Duration,
+    entries: Mutex<HashMap<(i64, String), (Instant, serde_json::Value)>>,
+}
+
+impl ResponseCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn get(&self, chat_id: i64, key: &str) -> Option<serde_json::Value> {
+        let entries = self.entries.lock().ok()?;
+        let (stored_at, value) = entries.get(&(chat_id, key.to_string()))?;
+        (stored_at.elapsed() < self.ttl).then(|| value.clone())
+    }
+
+    pub fn insert(&self, chat_id: i64, key: &str, value: serde_json::Value) {
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.retain(|_, (stored_at, _)| stored_at.elapsed() < self.ttl);
+            entries.insert((chat_id, key.to_string()), (Instant::now(), value));
+        }
+    }
+
+    /// Drops everything cached about the chat, e.g. after changing its members.
+    pub fn invalidate_chat(&self, chat_id: i64) {
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.retain(|(cached_chat_id, _), _| *cached_chat_id != chat_id);
+        }
+    }
+}
@@ -0,0 +1,128 @@
+use crate::http_client::HttpResponse;
+use std::fmt::Display;
+
+/// Why an API call failed, classified by how the caller should react.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ApiError {
+    /// The request did not reach the API or the response was cut off
+    Transport(String),
+    /// 401: the token is invalid or was revoked; retrying won't help
+    Unauthorized(String),
+    /// 409: another instance polls with the same token, or a webhook is set
+    Conflict(String),
+    /// 429: flood control, retry after the given number of seconds
+    RetryAfter(u64),
+    /// Any other error reported by the API
+    Api { code: i64, description: String },
+    /// The response is not the JSON the API promises
+    InvalidResponse(String),
+}
+
+impl ApiError {
+    /// Classifies a response with `"ok": false`.
+    pub fn from_response(resp: &serde_json::Value) -> Self {
+        let code = resp
+            .get("error_code")
+            .and_then(|code| code.as_i64())
+            .unwrap_or_default();
+        let description = resp
+            .get("description")
+            .and_then(|description| description.as_str())
+            .unwrap_or_default()
+            .to_string();
+        let retry_after = resp
+            .get("parameters")
+            .and_then(|parameters| parameters.get("retry_after"))
+            .and_then(|retry_after| retry_after.as_u64());
+        match (code, retry_after) {
+            (401, _) => ApiError::Unauthorized(description),
+            (409, _) => ApiError::Conflict(description),
+            (429, Some(retry_after)) => ApiError::RetryAfter(retry_after),
+            _ => ApiError::Api { code, description },
+        }
+    }
+
+    /// Classifies a response that isn't the JSON of the API, e.g. an error
+    /// page of a proxy or load balancer, by its HTTP status.
+    pub fn from_status(response: &HttpResponse) -> Self {
+        let description = format!("HTTP {}", response.status);
+        match response.status {
+            401 => ApiError::Unauthorized(description),
+            409 => ApiError::Conflict(description),
+            429 => ApiError::RetryAfter(
+                response
+                    .header("Retry-After")
+                    .and_then(|seconds| seconds.trim().parse().ok())
+                    .unwrap_or(1),
+            ),
+            status if status >= 500 => ApiError::Transport(description),
+            _ if response.is_success() => ApiError::InvalidResponse(description),
+            status => ApiError::Api {
+                code: status.into(),
+                description,
+            },
+        }
+    }
+
+    /// Returns true if retrying the same request may succeed.
+    pub fn is_retryable(&self) -> bool {
+        !matches!(self, ApiError::Unauthorized(_))
+    }
+}
+
+impl Display for ApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ApiError::Transport(err) => write!(f, "transport error: {err}"),
+            ApiError::Unauthorized(description) => write!(f, "unauthorized: {description}"),
+            ApiError::Conflict(description) => write!(f, "conflict: {description}"),
+            ApiError::RetryAfter(seconds) => write!(f, "rate limited for {seconds}s"),
+            ApiError::Api { code, description } => write!(f, "error {code}: {description}"),
+            ApiError::InvalidResponse(err) => write!(f, "invalid response: {err}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ApiError;
+    use serde_json::json;
+
+    fn error(code: i64, parameters: Option<serde_json::Value>) -> ApiError {
+        let mut resp = json!({ "ok": false, "error_code": code, "description": "Oops" });
+        if let Some(parameters) = parameters {
+            resp["parameters"] = parameters;
+        }
+        ApiError::from_response(&resp)
+    }
+
+    #[test]
+    fn classifies_unauthorized() {
+        let err = error(401, None);
+        assert_eq!(err, ApiError::Unauthorized(String::from("Oops")));
+        assert!(!err.is_retryable());
+    }
+
+    #[test]
+    fn classifies_conflict() {
+        let err = error(409, None);
+        assert_eq!(err, ApiError::Conflict(String::from("Oops")));
+        assert!(err.is_retryable());
+    }
+
+    #[test]
+    fn classifies_flood_control() {
+        assert_eq!(
+            error(429, Some(json!({ "retry_after": 7 }))),
+            ApiError::RetryAfter(7)
+        );
+        // Without a delay there is nothing to wait for.
+        assert_eq!(
+            error(429, None),
+            ApiError::Api {
+                code: 429,
+                description: String::from("Oops")
+            }
+        );
+    }
+}
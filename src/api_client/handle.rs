@@ -0,0 +1,124 @@
+use crate::api_client::api_client::ApiClient;
+use crate::api_client::types::{Edited, InputMedia, MessageTarget};
+use crate::http_client::HttpClient;
+use crate::types::{Message, MessageId};
+use async_trait::async_trait;
+
+/// Lets a sent or received message act as a handle for follow-up calls:
+///
+/// ```ignore
+/// let sent = api_client.send_message(chat_id, text).await?;
+/// sent.edit_text(&api_client, String::from("Done\\!")).await;
+/// ```
+#[async_trait]
+pub trait MessageHandle {
+    async fn edit_text<T>(&self, api_client: &ApiClient<T>, text: String) -> Option<Edited>
+    where
+        T: HttpClient + Send + Sync;
+
+    async fn edit_caption<T>(
+        &self,
+        api_client: &ApiClient<T>,
+        caption: Option<String>,
+    ) -> Option<Edited>
+    where
+        T: HttpClient + Send + Sync;
+
+    async fn edit_media<T>(&self, api_client: &ApiClient<T>, media: &InputMedia) -> Option<Edited>
+    where
+        T: HttpClient + Send + Sync;
+
+    async fn edit_reply_markup<T>(
+        &self,
+        api_client: &ApiClient<T>,
+        reply_markup: Option<&serde_json::Value>,
+    ) -> Option<Edited>
+    where
+        T: HttpClient + Send + Sync;
+
+    async fn delete<T>(&self, api_client: &ApiClient<T>) -> Option<bool>
+    where
+        T: HttpClient + Send + Sync;
+
+    async fn forward_to<T>(&self, api_client: &ApiClient<T>, chat_id: i64) -> Option<Message>
+    where
+        T: HttpClient + Send + Sync;
+
+    async fn copy_to<T>(&self, api_client: &ApiClient<T>, chat_id: i64) -> Option<MessageId>
+    where
+        T: HttpClient + Send + Sync;
+}
+
+#[async_trait]
+impl MessageHandle for Message {
+    async fn edit_text<T>(&self, api_client: &ApiClient<T>, text: String) -> Option<Edited>
+    where
+        T: HttpClient + Send + Sync,
+    {
+        api_client
+            .edit_message_text(&MessageTarget::from(self), text)
+            .await
+    }
+
+    async fn edit_caption<T>(
+        &self,
+        api_client: &ApiClient<T>,
+        caption: Option<String>,
+    ) -> Option<Edited>
+    where
+        T: HttpClient + Send + Sync,
+    {
+        api_client
+            .edit_message_caption(&MessageTarget::from(self), caption)
+            .await
+    }
+
+    async fn edit_media<T>(&self, api_client: &ApiClient<T>, media: &InputMedia) -> Option<Edited>
+    where
+        T: HttpClient + Send + Sync,
+    {
+        api_client
+            .edit_message_media(&MessageTarget::from(self), media)
+            .await
+    }
+
+    async fn edit_reply_markup<T>(
+        &self,
+        api_client: &ApiClient<T>,
+        reply_markup: Option<&serde_json::Value>,
+    ) -> Option<Edited>
+    where
+        T: HttpClient + Send + Sync,
+    {
+        api_client
+            .edit_message_reply_markup(&MessageTarget::from(self), reply_markup)
+            .await
+    }
+
+    async fn delete<T>(&self, api_client: &ApiClient<T>) -> Option<bool>
+    where
+        T: HttpClient + Send + Sync,
+    {
+        api_client
+            .delete_message(self.chat.id(), self.message_id)
+            .await
+    }
+
+    async fn forward_to<T>(&self, api_client: &ApiClient<T>, chat_id: i64) -> Option<Message>
+    where
+        T: HttpClient + Send + Sync,
+    {
+        api_client
+            .forward_message(chat_id, self.chat.id(), self.message_id)
+            .await
+    }
+
+    async fn copy_to<T>(&self, api_client: &ApiClient<T>, chat_id: i64) -> Option<MessageId>
+    where
+        T: HttpClient + Send + Sync,
+    {
+        api_client
+            .copy_message(chat_id, self.chat.id(), self.message_id, None)
+            .await
+    }
+}
@@ -0,0 +1,14 @@
+/// Characters that must be escaped in MarkdownV2 text.
+const RESERVED: &str = "\\_*[]()~`>#+-=|{}.!";
+
+/// Escapes text so it is sent verbatim with the MarkdownV2 parse mode.
+pub fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if RESERVED.contains(c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
@@ -1,18 +1,518 @@
+use crate::types::{LabeledPrice, Media, Message, PollType, ReplyMarkup, STARS};
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Payload {
     pub chat_id: i64,
+    /// Forum topic to send the message to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message_thread_id: Option<i64>,
+    /// Business account to send the message on behalf of
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub business_connection_id: Option<String>,
     pub text: String,
     pub parse_mode: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reply_markup: Option<ReplyMarkup>,
 }
 
 impl Payload {
     pub fn new(chat_id: i64, text: String, parse_mode: String) -> Self {
         Self {
             chat_id,
+            message_thread_id: None,
+            business_connection_id: None,
             text,
             parse_mode,
+            reply_markup: None,
         }
     }
+
+    pub fn in_topic(mut self, message_thread_id: Option<i64>) -> Self {
+        self.message_thread_id = message_thread_id;
+        self
+    }
+
+    pub fn via_business(mut self, business_connection_id: Option<String>) -> Self {
+        self.business_connection_id = business_connection_id;
+        self
+    }
+
+    pub fn with_markup(mut self, reply_markup: Option<ReplyMarkup>) -> Self {
+        self.reply_markup = reply_markup;
+        self
+    }
+}
+
+/// Parameters of `getUpdates`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GetUpdates {
+    pub offset: i64,
+    /// Long polling timeout in seconds
+    pub timeout: i64,
+    /// Most updates to return at once, 1-100
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u32>,
+    /// Update types to receive; Telegram keeps the previous setting if unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allowed_updates: Option<Vec<String>>,
+}
+
+/// Message to edit: one sent to a chat, or one sent via inline mode.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum MessageTarget {
+    Chat {
+        chat_id: i64,
+        message_id: i64,
+        /// Set for messages of a business account, which need it to be edited
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        business_connection_id: Option<String>,
+    },
+    Inline {
+        inline_message_id: String,
+    },
+}
+
+impl MessageTarget {
+    pub fn chat(chat_id: i64, message_id: i64) -> Self {
+        MessageTarget::Chat {
+            chat_id,
+            message_id,
+            business_connection_id: None,
+        }
+    }
+
+    /// Edits through the business connection, unless the target has one.
+    pub fn via_business(mut self, connection_id: Option<String>) -> Self {
+        if let MessageTarget::Chat {
+            business_connection_id: business_connection_id @ None,
+            ..
+        } = &mut self
+        {
+            *business_connection_id = connection_id;
+        }
+        self
+    }
+
+    pub fn inline(inline_message_id: impl Into<String>) -> Self {
+        MessageTarget::Inline {
+            inline_message_id: inline_message_id.into(),
+        }
+    }
+}
+
+impl From<&Message> for MessageTarget {
+    fn from(message: &Message) -> Self {
+        MessageTarget::chat(message.chat.id(), message.message_id)
+            .via_business(message.business_connection_id.clone())
+    }
+}
+
+/// Result of an edit: the edited message, or `true` for inline messages.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Edited {
+    Message(Box<Message>),
+    Inline(bool),
+}
+
+impl Edited {
+    pub fn message(self) -> Option<Message> {
+        match self {
+            Edited::Message(message) => Some(*message),
+            Edited::Inline(_) => None,
+        }
+    }
+}
+
+/// New content of a media message, as used by `editMessageMedia`.
+/// `media` is a file ID or an HTTP URL.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InputMedia {
+    #[serde(rename = "type")]
+    pub media_type: String,
+    pub media: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub caption: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parse_mode: Option<String>,
+}
+
+impl InputMedia {
+    fn new(media_type: &str, media: impl Into<String>) -> Self {
+        Self {
+            media_type: media_type.to_string(),
+            media: media.into(),
+            caption: None,
+            parse_mode: None,
+        }
+    }
+
+    pub fn photo(media: impl Into<String>) -> Self {
+        Self::new("photo", media)
+    }
+
+    pub fn video(media: impl Into<String>) -> Self {
+        Self::new("video", media)
+    }
+
+    pub fn animation(media: impl Into<String>) -> Self {
+        Self::new("animation", media)
+    }
+
+    pub fn audio(media: impl Into<String>) -> Self {
+        Self::new("audio", media)
+    }
+
+    pub fn document(media: impl Into<String>) -> Self {
+        Self::new("document", media)
+    }
+
+    /// Reuses media of a received message. Voice messages, video notes and
+    /// stickers cannot replace the media of a message.
+    pub fn from_media(media: &Media<'_>) -> Option<Self> {
+        match media {
+            Media::Photo(_)
+            | Media::Video(_)
+            | Media::Animation(_)
+            | Media::Audio(_)
+            | Media::Document(_) => Some(Self::new(media.field(), media.file_id()?)),
+            _ => None,
+        }
+    }
+
+    /// Sets a MarkdownV2 caption.
+    pub fn caption(mut self, caption: impl Into<String>) -> Self {
+        self.caption = Some(caption.into());
+        self.parse_mode = Some(String::from("MarkdownV2"));
+        self
+    }
+}
+
+/// Parameters of `sendPoll`:
+///
+/// ```ignore
+/// let poll = SendPoll::quiz("2 + 2?", ["3", "4", "5"], 1).explanation("Basic math");
+/// api_client.send_poll(chat_id, &poll).await;
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SendPoll {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message_thread_id: Option<i64>,
+    pub question: String,
+    /// 2-10 answer options, 1-100 characters each
+    pub options: Vec<InputPollOption>,
+    pub is_anonymous: bool,
+    #[serde(rename = "type")]
+    pub poll_type: PollType,
+    pub allows_multiple_answers: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub correct_option_id: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub explanation: Option<String>,
+    /// 5-600 seconds, can't be used together with `close_date`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub open_period: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub close_date: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InputPollOption {
+    pub text: String,
+}
+
+impl SendPoll {
+    fn new<I, S>(question: impl Into<String>, options: I, poll_type: PollType) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            message_thread_id: None,
+            question: question.into(),
+            options: options
+                .into_iter()
+                .map(|text| InputPollOption { text: text.into() })
+                .collect(),
+            is_anonymous: true,
+            poll_type,
+            allows_multiple_answers: false,
+            correct_option_id: None,
+            explanation: None,
+            open_period: None,
+            close_date: None,
+        }
+    }
+
+    pub fn regular<I, S>(question: impl Into<String>, options: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self::new(question, options, PollType::Regular)
+    }
+
+    /// A quiz with one correct answer, `correct_option_id` is 0-based.
+    pub fn quiz<I, S>(question: impl Into<String>, options: I, correct_option_id: usize) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let mut poll = Self::new(question, options, PollType::Quiz);
+        poll.correct_option_id = Some(correct_option_id);
+        poll
+    }
+
+    /// Forum topic to send the poll to.
+    pub fn in_topic(mut self, message_thread_id: Option<i64>) -> Self {
+        self.message_thread_id = message_thread_id;
+        self
+    }
+
+    /// Answers of non-anonymous polls are delivered as `poll_answer` updates.
+    pub fn anonymous(mut self, is_anonymous: bool) -> Self {
+        self.is_anonymous = is_anonymous;
+        self
+    }
+
+    /// Ignored by quizzes.
+    pub fn multiple_answers(mut self, allows_multiple_answers: bool) -> Self {
+        self.allows_multiple_answers = allows_multiple_answers;
+        self
+    }
+
+    /// Shown when a user chooses a wrong answer in a quiz.
+    pub fn explanation(mut self, explanation: impl Into<String>) -> Self {
+        self.explanation = Some(explanation.into());
+        self
+    }
+
+    pub fn open_period(mut self, seconds: i64) -> Self {
+        self.open_period = Some(seconds);
+        self.close_date = None;
+        self
+    }
+
+    pub fn close_date(mut self, close_date: i64) -> Self {
+        self.close_date = Some(close_date);
+        self.open_period = None;
+        self
+    }
+}
+
+/// Parameters of `sendInvoice` and `createInvoiceLink`. Digital goods are
+/// sold in Telegram Stars:
+///
+/// ```ignore
+/// let invoice = SendInvoice::stars("Sticker pack", "20 exclusive stickers", "pack:42", 50);
+/// api_client.send_invoice(chat_id, &invoice).await;
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SendInvoice {
+    /// Forum topic to send the invoice to, ignored by `createInvoiceLink`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message_thread_id: Option<i64>,
+    /// Product name, 1-32 characters
+    pub title: String,
+    /// Product description, 1-255 characters
+    pub description: String,
+    /// Bot-defined payload, 1-128 bytes, not displayed to the user
+    pub payload: String,
+    /// Empty for payments in Telegram Stars
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provider_token: Option<String>,
+    pub currency: String,
+    /// Exactly one item for payments in Telegram Stars
+    pub prices: Vec<LabeledPrice>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_parameter: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub photo_url: Option<String>,
+    pub need_name: bool,
+    pub need_email: bool,
+    pub need_shipping_address: bool,
+    /// The final price depends on the shipping method
+    pub is_flexible: bool,
+}
+
+impl SendInvoice {
+    pub fn new(
+        title: impl Into<String>,
+        description: impl Into<String>,
+        payload: impl Into<String>,
+        currency: impl Into<String>,
+        prices: Vec<LabeledPrice>,
+    ) -> Self {
+        Self {
+            message_thread_id: None,
+            title: title.into(),
+            description: description.into(),
+            payload: payload.into(),
+            provider_token: None,
+            currency: currency.into(),
+            prices,
+            start_parameter: None,
+            photo_url: None,
+            need_name: false,
+            need_email: false,
+            need_shipping_address: false,
+            is_flexible: false,
+        }
+    }
+
+    /// An invoice for digital goods priced in Telegram Stars.
+    pub fn stars(
+        title: impl Into<String>,
+        description: impl Into<String>,
+        payload: impl Into<String>,
+        amount: i64,
+    ) -> Self {
+        let title = title.into();
+        let prices = vec![LabeledPrice::new(title.clone(), amount)];
+        Self::new(title, description, payload, STARS, prices)
+    }
+
+    pub fn in_topic(mut self, message_thread_id: Option<i64>) -> Self {
+        self.message_thread_id = message_thread_id;
+        self
+    }
+
+    /// Payment provider token from @BotFather, for payments in fiat currencies.
+    pub fn provider_token(mut self, provider_token: impl Into<String>) -> Self {
+        self.provider_token = Some(provider_token.into());
+        self
+    }
+
+    pub fn start_parameter(mut self, start_parameter: impl Into<String>) -> Self {
+        self.start_parameter = Some(start_parameter.into());
+        self
+    }
+
+    pub fn photo_url(mut self, photo_url: impl Into<String>) -> Self {
+        self.photo_url = Some(photo_url.into());
+        self
+    }
+
+    pub fn need_name(mut self, value: bool) -> Self {
+        self.need_name = value;
+        self
+    }
+
+    pub fn need_email(mut self, value: bool) -> Self {
+        self.need_email = value;
+        self
+    }
+
+    /// Asks for a shipping address and sends shipping queries.
+    pub fn flexible_shipping(mut self, value: bool) -> Self {
+        self.need_shipping_address = value;
+        self.is_flexible = value;
+        self
+    }
+}
+
+/// A file to send.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputFile {
+    /// A file already on the Telegram servers
+    Id(String),
+    /// An HTTP URL Telegram downloads the file from
+    Url(String),
+    /// A `file://` URI read by a local Bot API server from its own disk,
+    /// without uploading. Only works in local mode.
+    Local(PathBuf),
+    /// A local file uploaded with multipart/form-data
+    Upload(PathBuf),
+}
+
+impl InputFile {
+    /// Picks the variant from a string: `file://` URIs, HTTP URLs and file IDs.
+    pub fn parse(value: &str) -> Self {
+        if let Some(path) = value.strip_prefix("file://") {
+            InputFile::Local(PathBuf::from(path))
+        } else if value.starts_with("http://") || value.starts_with("https://") {
+            InputFile::Url(value.to_string())
+        } else {
+            InputFile::Id(value.to_string())
+        }
+    }
+
+    pub fn upload(path: impl Into<PathBuf>) -> Self {
+        InputFile::Upload(path.into())
+    }
+
+    /// The value of the API parameter, `None` for uploads.
+    pub fn as_param(&self) -> Option<String> {
+        match self {
+            InputFile::Id(value) | InputFile::Url(value) => Some(value.clone()),
+            InputFile::Local(path) => Some(format!("file://{}", path.display())),
+            InputFile::Upload(_) => None,
+        }
+    }
+}
+
+/// How a file is sent: the `send*` method and its file parameter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileKind {
+    Photo,
+    Video,
+    Audio,
+    Document,
+    Animation,
+    Voice,
+    VideoNote,
+}
+
+impl FileKind {
+    pub fn field(&self) -> &'static str {
+        match self {
+            FileKind::Photo => "photo",
+            FileKind::Video => "video",
+            FileKind::Audio => "audio",
+            FileKind::Document => "document",
+            FileKind::Animation => "animation",
+            FileKind::Voice => "voice",
+            FileKind::VideoNote => "video_note",
+        }
+    }
+
+    pub fn send_method(&self) -> &'static str {
+        match self {
+            FileKind::Photo => "sendPhoto",
+            FileKind::Video => "sendVideo",
+            FileKind::Audio => "sendAudio",
+            FileKind::Document => "sendDocument",
+            FileKind::Animation => "sendAnimation",
+            FileKind::Voice => "sendVoice",
+            FileKind::VideoNote => "sendVideoNote",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MessageTarget;
+    use crate::types::fixtures::message;
+    use serde_json::json;
+
+    #[test]
+    fn edits_business_messages_through_their_connection() {
+        let target = MessageTarget::from(&message("hi").business("biz").build());
+        assert_eq!(
+            serde_json::to_value(&target).unwrap(),
+            json!({ "chat_id": 100, "message_id": 1, "business_connection_id": "biz" })
+        );
+        let reply = MessageTarget::from(&message("hi").build());
+        assert_eq!(
+            serde_json::to_value(reply.clone().via_business(Some(String::from("biz")))).unwrap(),
+            serde_json::to_value(&target).unwrap()
+        );
+        assert_eq!(
+            serde_json::to_value(&reply).unwrap(),
+            json!({ "chat_id": 100, "message_id": 1 })
+        );
+    }
 }
@@ -1,2 +1,14 @@
+pub use crate::commands::acl::{AccessDeniedHandler, AclCommandHandler};
+pub use crate::commands::donate::DonateCommandHandler;
 pub use crate::commands::echo::EchoCommandHandler;
+pub use crate::commands::failure::ErrorReplyHandler;
+pub use crate::commands::language::{locale_button, LanguageCommandHandler};
+pub use crate::commands::poll::PollCommandHandler;
+pub use crate::commands::schedule::{ReminderJobHandler, ScheduleCommandHandler, REMINDER_JOB};
+mod acl;
+mod donate;
 mod echo;
+mod failure;
+mod language;
+mod poll;
+mod schedule;
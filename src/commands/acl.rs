@@ -0,0 +1,113 @@
+use crate::api_client::{markdown, ApiClient};
+use crate::core::Bot;
+use crate::core::Command;
+use crate::core::CommandHandler;
+use crate::core::{Acl, BotCommand, Denial, DenialHandler, Role, RoleChangeError};
+use crate::http_client::HttpClient;
+use crate::i18n::Translator;
+use crate::t;
+use async_trait::async_trait;
+use std::sync::Arc;
+
+/// Replies to commands rejected by the access list.
+pub struct AccessDeniedHandler<T>
+where
+    T: HttpClient,
+{
+    api_client: Arc<ApiClient<T>>,
+}
+
+impl<T> AccessDeniedHandler<T>
+where
+    T: HttpClient,
+{
+    pub fn new(api_client: Arc<ApiClient<T>>) -> Self {
+        AccessDeniedHandler { api_client }
+    }
+}
+
+#[async_trait]
+impl<T> DenialHandler for AccessDeniedHandler<T>
+where
+    T: HttpClient + Send + Sync,
+{
+    async fn denied(&self, ctx: Arc<Bot>, command: Command, denial: Denial) {
+        let tr = ctx.i18n.for_message(&command.message).await;
+        let reply = match denial {
+            Denial::Banned => t!(tr, "acl.denied.banned"),
+            Denial::Role { required, .. } => t!(tr, "acl.denied.role", role = required),
+            Denial::ChatType(chat_type) => {
+                t!(tr, "acl.denied.chat_type", chat_type = chat_type)
+            }
+            Denial::Cooldown { retry_in } => t!(tr, "acl.denied.cooldown", count = retry_in),
+        };
+        self.api_client
+            .reply(&command.message, markdown::escape(&reply))
+            .await;
+    }
+}
+
+/// Changes user roles for the whole bot at runtime:
+/// `/grant <user_id> <role>` and `/revoke <user_id>`. Only bot admins and
+/// owners may use it, administrators of a group may not.
+pub struct AclCommandHandler<T>
+where
+    T: HttpClient,
+{
+    api_client: Arc<ApiClient<T>>,
+    acl: Arc<Acl>,
+}
+
+impl<T> AclCommandHandler<T>
+where
+    T: HttpClient,
+{
+    pub fn new(api_client: Arc<ApiClient<T>>, acl: Arc<Acl>) -> Self {
+        AclCommandHandler { api_client, acl }
+    }
+
+    async fn execute(&self, tr: &Translator, command: &Command) -> String {
+        let caller = match &command.message.from.0 {
+            Some(user) => user.id,
+            None => return t!(tr, "acl.bot_admins_only"),
+        };
+        let Some(user_id) = command.args.first().and_then(|id| id.parse::<i64>().ok()) else {
+            return t!(tr, "acl.usage");
+        };
+        let role = match command.command {
+            BotCommand::Grant => match command.args.get(1).and_then(|name| Role::from_name(name)) {
+                Some(role) => Some(role),
+                None => return t!(tr, "acl.unknown_role"),
+            },
+            _ => None,
+        };
+        match (self.acl.change_role(caller, user_id, role).await, role) {
+            (Ok(_), Some(role)) => t!(tr, "acl.granted", user_id = user_id, role = role),
+            (Ok(Some(previous)), None) => {
+                t!(tr, "acl.revoked", user_id = user_id, role = previous.role)
+            }
+            (Ok(None), None) => t!(tr, "acl.no_role", user_id = user_id),
+            (Err(RoleChangeError::NotBotAdmin), _) => t!(tr, "acl.bot_admins_only"),
+            (Err(RoleChangeError::OwnersOnlyUser), _) => {
+                t!(tr, "acl.owners_only.user", user_id = user_id)
+            }
+            (Err(RoleChangeError::OwnersOnlyRole(role)), _) => {
+                t!(tr, "acl.owners_only.role", role = role)
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl<T> CommandHandler for AclCommandHandler<T>
+where
+    T: HttpClient + Send + Sync,
+{
+    async fn handle(&self, ctx: Arc<Bot>, command: Command) {
+        let tr = ctx.i18n.for_message(&command.message).await;
+        let reply = self.execute(&tr, &command).await;
+        self.api_client
+            .reply(&command.message, markdown::escape(&reply))
+            .await;
+    }
+}
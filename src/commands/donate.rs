@@ -0,0 +1,121 @@
+use crate::api_client::{markdown, ApiClient, SendInvoice};
+use crate::core::Bot;
+use crate::core::Command;
+use crate::core::CommandHandler;
+use crate::http_client::HttpClient;
+use crate::payments::PaymentHandler;
+use crate::t;
+use crate::types::{Message, PreCheckoutQuery, SuccessfulPayment, STARS};
+use async_trait::async_trait;
+use std::sync::Arc;
+
+/// Prefix of the invoice payload of donations.
+const DONATION_PAYLOAD: &str = "donation:";
+
+const DEFAULT_STARS: i64 = 50;
+const MAX_STARS: i64 = 10_000;
+
+/// Stars of a donation, if the query pays a donation invoice with the
+/// amount it was sent with.
+fn donation_amount(query: &PreCheckoutQuery) -> Option<i64> {
+    let amount = query
+        .invoice_payload
+        .strip_prefix(DONATION_PAYLOAD)?
+        .parse::<i64>()
+        .ok()?;
+    (query.currency == STARS && query.total_amount == amount && (1..=MAX_STARS).contains(&amount))
+        .then_some(amount)
+}
+
+/// `/donate [stars]` sends an invoice in Telegram Stars and thanks the
+/// sender once it is paid.
+pub struct DonateCommandHandler<T>
+where
+    T: HttpClient,
+{
+    api_client: Arc<ApiClient<T>>,
+}
+
+impl<T> DonateCommandHandler<T>
+where
+    T: HttpClient,
+{
+    pub fn new(api_client: Arc<ApiClient<T>>) -> Self {
+        DonateCommandHandler { api_client }
+    }
+}
+
+#[async_trait]
+impl<T> CommandHandler for DonateCommandHandler<T>
+where
+    T: HttpClient + Send + Sync,
+{
+    async fn handle(&self, ctx: Arc<Bot>, command: Command) {
+        let chat_id = command.message.chat.id();
+        let tr = ctx.i18n.for_message(&command.message).await;
+        let amount = match command.args.first().map(|arg| arg.parse::<i64>()) {
+            None => DEFAULT_STARS,
+            Some(Ok(amount)) if (1..=MAX_STARS).contains(&amount) => amount,
+            Some(_) => {
+                let reply = t!(tr, "donate.usage", max = MAX_STARS);
+                self.api_client
+                    .reply(&command.message, markdown::escape(&reply))
+                    .await;
+                return;
+            }
+        };
+        let invoice = SendInvoice::stars(
+            t!(tr, "donate.title"),
+            t!(tr, "donate.description"),
+            format!("{DONATION_PAYLOAD}{amount}"),
+            amount,
+        )
+        .in_topic(command.message.reply_thread_id());
+        self.api_client.send_invoice(chat_id, &invoice).await;
+    }
+}
+
+#[async_trait]
+impl<T> PaymentHandler for DonateCommandHandler<T>
+where
+    T: HttpClient + Send + Sync,
+{
+    async fn pre_checkout(&self, ctx: Arc<Bot>, query: &PreCheckoutQuery) -> Result<(), String> {
+        if donation_amount(query).is_some() {
+            return Ok(());
+        }
+        let tr = ctx.i18n.for_user(&query.from).await;
+        Err(t!(tr, "donate.invalid"))
+    }
+
+    async fn paid(&self, ctx: Arc<Bot>, message: &Message, payment: &SuccessfulPayment) {
+        let tr = ctx.i18n.for_message(message).await;
+        let reply = t!(tr, "donate.thanks", count = payment.total_amount);
+        self.api_client
+            .reply(message, markdown::escape(&reply))
+            .await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{donation_amount, MAX_STARS};
+    use crate::types::fixtures::pre_checkout_query as query;
+
+    #[test]
+    fn accepts_only_the_invoiced_amount() {
+        assert_eq!(donation_amount(&query("XTR", 50, "donation:50")), Some(50));
+        assert_eq!(donation_amount(&query("XTR", 1, "donation:50")), None);
+        assert_eq!(donation_amount(&query("USD", 50, "donation:50")), None);
+        assert_eq!(donation_amount(&query("XTR", 50, "order:50")), None);
+    }
+
+    #[test]
+    fn rejects_amounts_out_of_range() {
+        assert_eq!(donation_amount(&query("XTR", 0, "donation:0")), None);
+        assert_eq!(donation_amount(&query("XTR", -5, "donation:-5")), None);
+        let above = MAX_STARS + 1;
+        let payload = format!("donation:{above}");
+        assert_eq!(donation_amount(&query("XTR", above, &payload)), None);
+    }
+}
@@ -1,7 +1,8 @@
-use crate::api_client::ApiClient;
+use crate::api_client::{ApiClient, MessageTarget};
 use crate::core::Bot;
 use crate::core::Command;
 use crate::core::CommandHandler;
+use crate::core::{EditPolicy, EditedCommand};
 use crate::http_client::HttpClient;
 use async_trait::async_trait;
 use std::sync::Arc;
@@ -29,16 +30,57 @@ where
 {
     async fn handle(&self, ctx: Arc<Bot>, command: Command) {
         let _ = ctx;
+        if let Some(media) = command.message.media() {
+            self.api_client
+                .reply_media(
+                    &command.message,
+                    &media,
+                    command.message.caption().map(String::from),
+                )
+                .await;
+            return;
+        }
         self.api_client
-            .send_message(
-                command.message.chat.id(),
-                command
-                    .message
-                    .text
-                    .as_ref()
-                    .map(|text| text.clone())
-                    .unwrap_or_default(),
+            .reply(
+                &command.message,
+                command.message.text.clone().unwrap_or_default(),
             )
             .await;
     }
+
+    fn edit_policy(&self) -> EditPolicy {
+        EditPolicy::Deliver
+    }
+
+    /// Updates the echo in place instead of echoing the edited message again,
+    /// or echoes it anew if the echo can't be edited.
+    async fn handle_edited(&self, ctx: Arc<Bot>, edited: EditedCommand) {
+        let message = &edited.command.message;
+        let Some(reply) = &edited.previous_reply else {
+            return self.handle(ctx, edited.command).await;
+        };
+        // A business bot's reply may lack the connection its chat is in.
+        let target =
+            MessageTarget::from(reply).via_business(message.business_connection_id.clone());
+        let result = if reply.text.is_some() && message.text.is_some() {
+            if reply.text == message.text {
+                return;
+            }
+            self.api_client
+                .edit_message_text(&target, message.text.clone().unwrap_or_default())
+                .await
+        } else if reply.media().is_some() && message.media().is_some() {
+            if reply.caption() == message.caption() {
+                return;
+            }
+            self.api_client
+                .edit_message_caption(&target, message.caption().map(String::from))
+                .await
+        } else {
+            None
+        };
+        if result.is_none() {
+            self.handle(ctx, edited.command).await;
+        }
+    }
 }
@@ -0,0 +1,41 @@
+use crate::api_client::{markdown, ApiClient};
+use crate::core::{Bot, ErrorHandler, HandlerFailure};
+use crate::http_client::HttpClient;
+use crate::t;
+use async_trait::async_trait;
+use std::sync::Arc;
+
+/// Tells the user something went wrong when a handler panics or times out.
+pub struct ErrorReplyHandler<T>
+where
+    T: HttpClient,
+{
+    api_client: Arc<ApiClient<T>>,
+}
+
+impl<T> ErrorReplyHandler<T>
+where
+    T: HttpClient,
+{
+    pub fn new(api_client: Arc<ApiClient<T>>) -> Self {
+        ErrorReplyHandler { api_client }
+    }
+}
+
+#[async_trait]
+impl<T> ErrorHandler for ErrorReplyHandler<T>
+where
+    T: HttpClient + Send + Sync,
+{
+    async fn failed(&self, ctx: Arc<Bot>, failure: HandlerFailure) {
+        // Events without a message, e.g. poll answers, have nobody to tell.
+        let Some(message) = failure.message else {
+            return;
+        };
+        let tr = ctx.i18n.for_message(&message).await;
+        let reply = t!(tr, "error.generic");
+        self.api_client
+            .reply(&message, markdown::escape(&reply))
+            .await;
+    }
+}
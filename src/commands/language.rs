@@ -0,0 +1,91 @@
+use crate::api_client::{markdown, ApiClient};
+use crate::core::Bot;
+use crate::core::BotCommand;
+use crate::core::Command;
+use crate::core::CommandHandler;
+use crate::core::EditPolicy;
+use crate::http_client::HttpClient;
+use crate::storage::Namespace;
+use crate::t;
+use crate::types::{KeyboardButton, ReplyKeyboardMarkup, ReplyKeyboardRemove, ReplyMarkup};
+use async_trait::async_trait;
+use std::sync::Arc;
+
+/// Text of the reply keyboard button that picks the locale with `/language`.
+pub fn locale_button(locale: &str) -> String {
+    format!("🌐 {locale}")
+}
+
+/// Sets the reply language: `/language <code>` for the sender and
+/// `/chatlanguage <code>` for the whole chat. `reset` removes the override.
+pub struct LanguageCommandHandler<T>
+where
+    T: HttpClient,
+{
+    api_client: Arc<ApiClient<T>>,
+}
+
+impl<T> LanguageCommandHandler<T>
+where
+    T: HttpClient,
+{
+    pub fn new(api_client: Arc<ApiClient<T>>) -> Self {
+        LanguageCommandHandler { api_client }
+    }
+}
+
+#[async_trait]
+impl<T> CommandHandler for LanguageCommandHandler<T>
+where
+    T: HttpClient + Send + Sync,
+{
+    async fn handle(&self, ctx: Arc<Bot>, command: Command) {
+        let ns = match (command.command, &command.message.from.0) {
+            (BotCommand::ChatLanguage, _) => Namespace::Chat(command.message.chat.id()),
+            (_, Some(user)) => Namespace::User(user.id),
+            (_, None) => return,
+        };
+        let i18n = &ctx.i18n;
+        let mut markup = None;
+        let reply = match command.args.first().map(String::as_str) {
+            None => {
+                let tr = i18n.for_message(&command.message).await;
+                let locales = i18n.translations().locales();
+                // Offer the locales as buttons, pressing one runs `/language <locale>`.
+                if command.command == BotCommand::Language {
+                    let buttons = locales
+                        .iter()
+                        .map(|locale| KeyboardButton::text(&locale_button(locale)))
+                        .collect();
+                    markup = Some(ReplyMarkup::from(
+                        ReplyKeyboardMarkup::new().row(buttons).resize().one_time(),
+                    ));
+                }
+                t!(tr, "language.usage", locales = locales.join(", "))
+            }
+            Some("reset") => {
+                i18n.clear_override(&ns).await;
+                t!(i18n.for_message(&command.message).await, "language.reset")
+            }
+            Some(tag) => match i18n.set_override(&ns, tag).await {
+                Some(locale) => t!(i18n.translator(&locale), "language.set", locale = locale),
+                None => t!(
+                    i18n.for_message(&command.message).await,
+                    "language.unknown",
+                    language = tag
+                ),
+            },
+        };
+        if command.command == BotCommand::Language && !command.args.is_empty() {
+            markup = Some(ReplyMarkup::from(ReplyKeyboardRemove::new()));
+        }
+        self.api_client
+            .reply_with_markup(&command.message, markdown::escape(&reply), markup)
+            .await;
+    }
+
+    /// Setting the language again is harmless, so a corrected code applies.
+    fn edit_policy(&self) -> EditPolicy {
+        EditPolicy::Rerun
+    }
+}
@@ -0,0 +1,85 @@
+use crate::api_client::{markdown, ApiClient, SendPoll};
+use crate::core::Bot;
+use crate::core::BotCommand;
+use crate::core::Command;
+use crate::core::CommandHandler;
+use crate::core::Cooldown;
+use crate::http_client::HttpClient;
+use crate::polls::PollTracker;
+use crate::t;
+use async_trait::async_trait;
+use std::sync::Arc;
+
+/// Starts and summarizes polls: `/poll <question> | <option> | <option>...`
+/// sends a non-anonymous poll, `/results` shows the votes of the last one.
+pub struct PollCommandHandler<T>
+where
+    T: HttpClient,
+{
+    api_client: Arc<ApiClient<T>>,
+    tracker: Arc<PollTracker>,
+}
+
+impl<T> PollCommandHandler<T>
+where
+    T: HttpClient,
+{
+    pub fn new(api_client: Arc<ApiClient<T>>, tracker: Arc<PollTracker>) -> Self {
+        PollCommandHandler {
+            api_client,
+            tracker,
+        }
+    }
+}
+
+#[async_trait]
+impl<T> CommandHandler for PollCommandHandler<T>
+where
+    T: HttpClient + Send + Sync,
+{
+    /// Polls flood a group quickly, allow a few per minute.
+    fn cooldown(&self) -> Option<Cooldown> {
+        Some(Cooldown::per_chat(60).burst(3))
+    }
+
+    async fn handle(&self, ctx: Arc<Bot>, command: Command) {
+        let chat_id = command.message.chat.id();
+        let tr = ctx.i18n.for_message(&command.message).await;
+        let reply = match command.command {
+            BotCommand::Poll => {
+                let text = command.args.join(" ");
+                let mut parts = text
+                    .split('|')
+                    .map(str::trim)
+                    .filter(|part| !part.is_empty());
+                let question = parts.next().unwrap_or_default();
+                let options: Vec<&str> = parts.collect();
+                if !(2..=10).contains(&options.len()) {
+                    t!(tr, "poll.usage")
+                } else {
+                    let poll = SendPoll::regular(question, options)
+                        .anonymous(false)
+                        .in_topic(command.message.reply_thread_id());
+                    match self.api_client.send_poll(chat_id, &poll).await {
+                        Some(sent) => {
+                            self.tracker.track(&sent).await;
+                            return;
+                        }
+                        None => t!(tr, "poll.failed"),
+                    }
+                }
+            }
+            _ => match self.tracker.last_in_chat(chat_id).await {
+                Some(tally) => format!(
+                    "{}\n{}",
+                    tally.summary(),
+                    t!(tr, "poll.voters", count = tally.voters())
+                ),
+                None => t!(tr, "poll.none"),
+            },
+        };
+        self.api_client
+            .reply(&command.message, markdown::escape(&reply))
+            .await;
+    }
+}
@@ -0,0 +1,240 @@
+use crate::api_client::{markdown, ApiClient};
+use crate::core::Bot;
+use crate::core::BotCommand;
+use crate::core::Command;
+use crate::core::CommandHandler;
+use crate::http_client::HttpClient;
+use crate::i18n::Translator;
+use crate::scheduler::{format_utc, now, Job, JobHandler, Schedule, Scheduler};
+use crate::t;
+use crate::types::Message;
+use async_trait::async_trait;
+use std::sync::Arc;
+
+/// Job kind of reminders created with `/remind` and `/every`.
+pub const REMINDER_JOB: &str = "reminder";
+
+/// Parses durations like `30s`, `10m`, `2h`, `1d` or `1h30m` into seconds.
+/// Returns `None` for durations too long to represent.
+fn parse_duration(value: &str) -> Option<i64> {
+    let mut total: i64 = 0;
+    let mut number = String::new();
+    for c in value.chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+        let unit = match c {
+            's' => 1,
+            'm' => 60,
+            'h' => 60 * 60,
+            'd' => 24 * 60 * 60,
+            _ => return None,
+        };
+        let seconds = number.parse::<i64>().ok()?.checked_mul(unit)?;
+        total = total.checked_add(seconds)?;
+        number.clear();
+    }
+    if !number.is_empty() || total == 0 {
+        return None;
+    }
+    Some(total)
+}
+
+/// How often a job runs, for `/jobs`.
+fn describe(tr: &Translator, schedule: &Schedule) -> String {
+    match schedule {
+        Schedule::Once { at } => t!(tr, "schedule.once", time = format_utc(*at)),
+        Schedule::Cron { expr } => t!(tr, "schedule.cron", expr = expr),
+    }
+}
+
+/// Sends the reminder text to the chat the job belongs to.
+pub struct ReminderJobHandler<T>
+where
+    T: HttpClient,
+{
+    api_client: Arc<ApiClient<T>>,
+}
+
+impl<T> ReminderJobHandler<T>
+where
+    T: HttpClient,
+{
+    pub fn new(api_client: Arc<ApiClient<T>>) -> Self {
+        ReminderJobHandler { api_client }
+    }
+}
+
+#[async_trait]
+impl<T> JobHandler for ReminderJobHandler<T>
+where
+    T: HttpClient + Send + Sync,
+{
+    async fn run(&self, job: &Job) {
+        let text = job
+            .payload
+            .get("text")
+            .and_then(|text| text.as_str())
+            .unwrap_or_default();
+        let message_thread_id = job
+            .payload
+            .get("message_thread_id")
+            .and_then(|id| id.as_i64());
+        self.api_client
+            .send_message_to_topic(job.chat_id, message_thread_id, markdown::escape(text))
+            .await;
+    }
+}
+
+/// Manages the jobs of a chat:
+/// `/remind <10m|2h|1d> <text>`, `/every <cron expression> <text>`,
+/// `/jobs` and `/canceljob <id>`.
+pub struct ScheduleCommandHandler<T>
+where
+    T: HttpClient,
+{
+    api_client: Arc<ApiClient<T>>,
+    scheduler: Arc<Scheduler>,
+}
+
+impl<T> ScheduleCommandHandler<T>
+where
+    T: HttpClient,
+{
+    pub fn new(api_client: Arc<ApiClient<T>>, scheduler: Arc<Scheduler>) -> Self {
+        ScheduleCommandHandler {
+            api_client,
+            scheduler,
+        }
+    }
+
+    /// Schedules a reminder to the chat and forum topic of the message.
+    async fn add_reminder(
+        &self,
+        tr: &Translator,
+        message: &Message,
+        schedule: Schedule,
+        text: &[String],
+    ) -> String {
+        if text.is_empty() {
+            return t!(tr, "schedule.no_text");
+        }
+        let mut payload = serde_json::json!({ "text": text.join(" ") });
+        if let Some(message_thread_id) = message.reply_thread_id() {
+            payload["message_thread_id"] = message_thread_id.into();
+        }
+        match self
+            .scheduler
+            .schedule(message.chat.id(), schedule, REMINDER_JOB, payload)
+            .await
+        {
+            Ok(id) => t!(tr, "schedule.scheduled", id = id),
+            Err(err) => t!(tr, "schedule.failed", error = err),
+        }
+    }
+
+    async fn execute(&self, tr: &Translator, command: &Command) -> String {
+        let chat_id = command.message.chat.id();
+        let args = &command.args;
+        match command.command {
+            BotCommand::Remind => {
+                let Some(at) = args
+                    .first()
+                    .and_then(|delay| parse_duration(delay))
+                    .and_then(|delay| now().checked_add(delay))
+                else {
+                    return t!(tr, "schedule.remind_usage");
+                };
+                let schedule = Schedule::Once { at };
+                self.add_reminder(tr, &command.message, schedule, &args[1..])
+                    .await
+            }
+            BotCommand::Every => {
+                if args.len() < 5 {
+                    return t!(tr, "schedule.every_usage");
+                }
+                match Schedule::cron(&args[..5].join(" ")) {
+                    Ok(schedule) => {
+                        self.add_reminder(tr, &command.message, schedule, &args[5..])
+                            .await
+                    }
+                    Err(err) => t!(tr, "schedule.invalid_cron", error = err),
+                }
+            }
+            BotCommand::Jobs => {
+                let jobs = self.scheduler.jobs_for_chat(chat_id).await;
+                if jobs.is_empty() {
+                    return t!(tr, "schedule.none");
+                }
+                jobs.iter()
+                    .map(|job| {
+                        t!(
+                            tr,
+                            "schedule.job",
+                            id = job.id,
+                            kind = job.kind,
+                            schedule = describe(tr, &job.schedule),
+                            next_run = format_utc(job.next_run)
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            }
+            BotCommand::CancelJob => {
+                let Some(id) = args
+                    .first()
+                    .and_then(|id| id.trim_start_matches('#').parse().ok())
+                else {
+                    return t!(tr, "schedule.cancel_usage");
+                };
+                if self.scheduler.cancel(chat_id, id).await {
+                    t!(tr, "schedule.cancelled", id = id)
+                } else {
+                    t!(tr, "schedule.not_found", id = id)
+                }
+            }
+            _ => String::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl<T> CommandHandler for ScheduleCommandHandler<T>
+where
+    T: HttpClient + Send + Sync,
+{
+    async fn handle(&self, ctx: Arc<Bot>, command: Command) {
+        let tr = ctx.i18n.for_message(&command.message).await;
+        let reply = self.execute(&tr, &command).await;
+        self.api_client
+            .reply(&command.message, markdown::escape(&reply))
+            .await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_duration;
+
+    #[test]
+    fn parses_units() {
+        assert_eq!(parse_duration("30s"), Some(30));
+        assert_eq!(parse_duration("1h30m"), Some(5400));
+        assert_eq!(parse_duration("2d"), Some(172_800));
+    }
+
+    #[test]
+    fn rejects_invalid_input() {
+        assert_eq!(parse_duration("10"), None);
+        assert_eq!(parse_duration("0m"), None);
+        assert_eq!(parse_duration("5x"), None);
+    }
+
+    #[test]
+    fn rejects_overflow() {
+        assert_eq!(parse_duration("99999999999999999d"), None);
+        assert_eq!(parse_duration("99999999999999999999s"), None);
+        assert_eq!(parse_duration("9223372036854775807s1s"), None);
+    }
+}
@@ -1,8 +1,25 @@
+pub use crate::core::acl::{
+    Acl, AclConfig, ChatAdminSource, CommandPolicy, Denial, DenialHandler, Role, RoleChangeError,
+    RoleGrant,
+};
 pub use crate::core::bot::Bot;
 pub use crate::core::command_registry::Registry;
 pub use crate::core::commands::{
-    decide_command, BotCommand, Command, CommandHandler, CommandRegistry,
+    decide_command, BotCommand, Command, CommandHandler, CommandRegistry, EditPolicy, EditedCommand,
+};
+pub use crate::core::cooldown::{Cooldown, CooldownScope};
+pub use crate::core::events::{route_update, Event, EventHandler, EventKind, Incoming};
+pub use crate::core::failures::{
+    panic_message, ErrorHandler, HandlerError, HandlerFailure, DEFAULT_HANDLER_TIMEOUT,
 };
+pub use crate::core::replies::ReplyLog;
+pub use crate::core::token::Token;
+pub mod acl;
 pub mod bot;
 mod command_registry;
 mod commands;
+mod cooldown;
+mod events;
+mod failures;
+mod replies;
+pub mod token;
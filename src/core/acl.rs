@@ -0,0 +1,503 @@
+use crate::core::bot::Bot;
+use crate::core::commands::{BotCommand, Command};
+use crate::core::cooldown::Cooldown;
+use crate::storage::{Namespace, Storage};
+use crate::types::{ChatType, Message};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// Prefix of the global storage keys holding roles assigned with `/grant`,
+/// followed by the user ID.
+const ROLE_KEY_PREFIX: &str = "acl.role.";
+
+/// Role of a user, ordered from the least to the most privileged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    Banned,
+    User,
+    Admin,
+    Owner,
+}
+
+impl Role {
+    pub fn from_name(name: &str) -> Option<Role> {
+        match name.to_lowercase().as_str() {
+            "banned" => Some(Role::Banned),
+            "user" => Some(Role::User),
+            "admin" => Some(Role::Admin),
+            "owner" => Some(Role::Owner),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Role {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Role::Banned => "banned",
+            Role::User => "user",
+            Role::Admin => "admin",
+            Role::Owner => "owner",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// A role assigned to a user for the whole bot, with the role of whoever
+/// assigned it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RoleGrant {
+    pub role: Role,
+    /// `Owner` for roles from the config
+    pub granted_by: Role,
+}
+
+impl RoleGrant {
+    /// Least privileged role that may change the grant: only owners change
+    /// admins and owners, and nobody undoes what a higher role did.
+    pub fn changeable_by(&self) -> Role {
+        if self.role >= Role::Admin {
+            Role::Owner
+        } else {
+            self.granted_by.max(Role::Admin)
+        }
+    }
+}
+
+/// Why a role change was refused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RoleChangeError {
+    /// Roles apply in every chat, so only bot admins and owners change them,
+    /// not administrators of one group
+    NotBotAdmin,
+    /// The role of the user can only be changed by an owner
+    OwnersOnlyUser,
+    /// The role can only be granted by an owner
+    OwnersOnlyRole(Role),
+}
+
+/// Who may run a command and where.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommandPolicy {
+    /// The least privileged role allowed to run the command.
+    #[serde(default = "CommandPolicy::default_role")]
+    pub role: Role,
+    /// Chat types the command may be used in. Any chat type if not set.
+    #[serde(default)]
+    pub chat_types: Option<Vec<ChatType>>,
+    /// Overrides the cooldown declared by the command handler.
+    #[serde(default)]
+    pub cooldown: Option<Cooldown>,
+}
+
+impl CommandPolicy {
+    fn default_role() -> Role {
+        Role::User
+    }
+
+    pub fn new(role: Role) -> Self {
+        Self {
+            role,
+            chat_types: None,
+            cooldown: None,
+        }
+    }
+
+    pub fn in_chats(mut self, chat_types: &[ChatType]) -> Self {
+        self.chat_types = Some(chat_types.to_vec());
+        self
+    }
+
+    pub fn with_cooldown(mut self, cooldown: Cooldown) -> Self {
+        self.cooldown = Some(cooldown);
+        self
+    }
+}
+
+/// Access control settings, usually read from the JSON file set in `ACL_CONFIG`:
+///
+/// ```json
+/// {
+///     "owners": [1234],
+///     "admins": [5678],
+///     "banned": [],
+///     "chat_admins": true,
+///     "commands": {
+///         "video": {
+///             "role": "user",
+///             "chat_types": ["private"],
+///             "cooldown": { "scope": "user", "window": 60, "burst": 2 }
+///         }
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AclConfig {
+    #[serde(default)]
+    pub owners: Vec<i64>,
+    #[serde(default)]
+    pub admins: Vec<i64>,
+    #[serde(default)]
+    pub banned: Vec<i64>,
+    /// Treat administrators of a group as bot admins inside that group.
+    #[serde(default)]
+    pub chat_admins: bool,
+    /// Policies by command name, without the leading slash.
+    #[serde(default)]
+    pub commands: HashMap<String, CommandPolicy>,
+}
+
+impl AclConfig {
+    pub fn from_file(path: &str) -> Option<Self> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|err| println!("Error reading ACL config {path}: {err}"))
+            .ok()?;
+        serde_json::from_str(&content)
+            .map_err(|err| println!("Error parsing ACL config {path}: {err}"))
+            .ok()
+    }
+}
+
+/// Reason why a command was rejected.
+#[derive(Debug, Clone)]
+pub enum Denial {
+    Banned,
+    Role {
+        required: Role,
+        actual: Role,
+    },
+    ChatType(ChatType),
+    /// The command is on cooldown, retry in that many seconds
+    Cooldown {
+        retry_in: u64,
+    },
+}
+
+/// Tells whether a user administers a chat.
+#[async_trait]
+pub trait ChatAdminSource: Send + Sync {
+    async fn is_chat_admin(&self, chat_id: i64, user_id: i64) -> bool;
+}
+
+/// Called instead of the command handler when a command is rejected.
+#[async_trait]
+pub trait DenialHandler: Send + Sync {
+    async fn denied(&self, ctx: Arc<Bot>, command: Command, denial: Denial);
+}
+
+/// Role based access control for registered commands.
+pub struct Acl {
+    roles: RwLock<HashMap<i64, RoleGrant>>,
+    policies: RwLock<HashMap<BotCommand, CommandPolicy>>,
+    chat_admins: bool,
+    admin_source: RwLock<Option<Arc<dyn ChatAdminSource>>>,
+    /// Where roles assigned at runtime are kept, see `load`.
+    storage: RwLock<Option<Arc<dyn Storage>>>,
+}
+
+impl Acl {
+    pub fn new(config: AclConfig) -> Arc<Self> {
+        let mut roles = HashMap::new();
+        for (ids, role) in [
+            (&config.admins, Role::Admin),
+            (&config.owners, Role::Owner),
+            (&config.banned, Role::Banned),
+        ] {
+            for id in ids {
+                let grant = RoleGrant {
+                    role,
+                    granted_by: Role::Owner,
+                };
+                roles.insert(*id, grant);
+            }
+        }
+        let mut policies = HashMap::from([
+            (BotCommand::Grant, CommandPolicy::new(Role::Admin)),
+            (BotCommand::Revoke, CommandPolicy::new(Role::Admin)),
+            (BotCommand::ChatLanguage, CommandPolicy::new(Role::Admin)),
+        ]);
+        for (name, policy) in config.commands {
+            match BotCommand::from_name(&name) {
+                BotCommand::Unknown => println!("ACL config: unknown command <{name}>"),
+                command => {
+                    policies.insert(command, policy);
+                }
+            }
+        }
+        Arc::new(Self {
+            roles: RwLock::new(roles),
+            policies: RwLock::new(policies),
+            chat_admins: config.chat_admins,
+            admin_source: RwLock::new(None),
+            storage: RwLock::new(None),
+        })
+    }
+
+    /// Loads the roles assigned at runtime, which override the config, and
+    /// keeps later changes in `storage`.
+    pub async fn load(&self, storage: Arc<dyn Storage>) {
+        let stored: Vec<(String, RoleGrant)> =
+            storage.scan(&Namespace::Global, ROLE_KEY_PREFIX).await;
+        let mut roles = self.roles.write().await;
+        for (key, grant) in stored {
+            match key[ROLE_KEY_PREFIX.len()..].parse() {
+                Ok(user_id) => {
+                    roles.insert(user_id, grant);
+                }
+                Err(_) => println!("Ignoring stored role under <{key}>"),
+            }
+        }
+        *self.storage.write().await = Some(storage);
+    }
+
+    async fn save(&self, user_id: i64, grant: Option<RoleGrant>) {
+        let Some(storage) = self.storage.read().await.clone() else {
+            return;
+        };
+        let key = format!("{ROLE_KEY_PREFIX}{user_id}");
+        match grant {
+            Some(grant) => storage.set(&Namespace::Global, &key, &grant).await,
+            None => {
+                storage.delete(&Namespace::Global, &key).await;
+            }
+        }
+    }
+
+    /// Sets where chat administrator status is looked up.
+    pub async fn set_admin_source(&self, source: Arc<dyn ChatAdminSource>) {
+        *self.admin_source.write().await = Some(source);
+    }
+
+    /// Assigns a role for the whole bot, as an owner would.
+    pub async fn set_role(&self, user_id: i64, role: Role) {
+        let grant = RoleGrant {
+            role,
+            granted_by: Role::Owner,
+        };
+        self.roles.write().await.insert(user_id, grant);
+        self.save(user_id, Some(grant)).await;
+    }
+
+    /// Assigns `role` to a user on behalf of `caller`, or removes the
+    /// assigned role if `role` is `None`, so the user falls back to the
+    /// default. Returns the previous assignment.
+    pub async fn change_role(
+        &self,
+        caller: i64,
+        user_id: i64,
+        role: Option<Role>,
+    ) -> Result<Option<RoleGrant>, RoleChangeError> {
+        let mut roles = self.roles.write().await;
+        // Group administrators are admins only in their group.
+        let caller = roles.get(&caller).map_or(Role::User, |grant| grant.role);
+        if caller < Role::Admin {
+            return Err(RoleChangeError::NotBotAdmin);
+        }
+        let previous = roles.get(&user_id).copied();
+        if previous.is_some_and(|grant| caller < grant.changeable_by()) {
+            return Err(RoleChangeError::OwnersOnlyUser);
+        }
+        let grant = match role {
+            Some(role) if role >= Role::Admin && caller < Role::Owner => {
+                return Err(RoleChangeError::OwnersOnlyRole(role));
+            }
+            Some(role) => {
+                let grant = RoleGrant {
+                    role,
+                    granted_by: caller,
+                };
+                roles.insert(user_id, grant);
+                Some(grant)
+            }
+            None => {
+                roles.remove(&user_id);
+                None
+            }
+        };
+        self.save(user_id, grant).await;
+        Ok(previous)
+    }
+
+    pub async fn set_policy(&self, command: BotCommand, policy: CommandPolicy) {
+        self.policies.write().await.insert(command, policy);
+    }
+
+    /// Resolves the role of a user in a chat. Explicitly assigned roles win,
+    /// then group administrators are admins if `chat_admins` is enabled.
+    pub async fn role_of(&self, user_id: i64, chat_id: i64, chat_type: ChatType) -> Role {
+        if let Some(grant) = self.roles.read().await.get(&user_id) {
+            return grant.role;
+        }
+        if self.chat_admins && chat_type.is_group() {
+            if let Some(source) = self.admin_source.read().await.clone() {
+                if source.is_chat_admin(chat_id, user_id).await {
+                    return Role::Admin;
+                }
+            }
+        }
+        Role::User
+    }
+
+    /// Role of the sender of a message in its chat.
+    pub async fn sender_role(&self, message: &Message) -> Role {
+        let chat = &message.chat;
+        match &message.from.0 {
+            Some(user) => self.role_of(user.id, chat.id(), chat.chat_type()).await,
+            None => Role::User,
+        }
+    }
+
+    /// Cooldown of the command set in the config, if any.
+    pub async fn cooldown(&self, command: BotCommand) -> Option<Cooldown> {
+        self.policies.read().await.get(&command)?.cooldown
+    }
+
+    /// Admins and owners are not limited by cooldowns.
+    pub async fn skips_cooldowns(&self, message: &Message) -> bool {
+        self.sender_role(message).await >= Role::Admin
+    }
+
+    /// Checks whether the sender of the command may run it.
+    pub async fn check(&self, command: &Command) -> Result<(), Denial> {
+        let chat = &command.message.chat;
+        let role = self.sender_role(&command.message).await;
+        if role == Role::Banned {
+            return Err(Denial::Banned);
+        }
+        let policies = self.policies.read().await;
+        let Some(policy) = policies.get(&command.command) else {
+            return Ok(());
+        };
+        if role < policy.role {
+            return Err(Denial::Role {
+                required: policy.role,
+                actual: role,
+            });
+        }
+        match &policy.chat_types {
+            Some(chat_types) if !chat_types.contains(&chat.chat_type()) => {
+                Err(Denial::ChatType(chat.chat_type()))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Acl, AclConfig, ChatAdminSource, Role, RoleChangeError};
+    use crate::storage::{MemoryStorage, Storage};
+    use crate::types::fixtures::message;
+    use crate::types::ChatType;
+    use async_trait::async_trait;
+    use std::sync::Arc;
+
+    const OWNER: i64 = 1;
+    const ADMIN: i64 = 2;
+    const GROUP_ADMIN: i64 = 3;
+    const USER: i64 = 9;
+
+    /// `GROUP_ADMIN` administers chat -100 and nothing else.
+    struct OneGroup;
+
+    #[async_trait]
+    impl ChatAdminSource for OneGroup {
+        async fn is_chat_admin(&self, chat_id: i64, user_id: i64) -> bool {
+            chat_id == -100 && user_id == GROUP_ADMIN
+        }
+    }
+
+    async fn acl() -> Arc<Acl> {
+        let acl = Acl::new(AclConfig {
+            owners: vec![OWNER],
+            admins: vec![ADMIN],
+            chat_admins: true,
+            ..AclConfig::default()
+        });
+        acl.set_admin_source(Arc::new(OneGroup)).await;
+        acl
+    }
+
+    #[tokio::test]
+    async fn group_admins_cannot_change_roles_in_other_chats() {
+        let acl = acl().await;
+        assert_eq!(
+            acl.role_of(GROUP_ADMIN, -100, ChatType::Supergroup).await,
+            Role::Admin
+        );
+        assert_eq!(
+            acl.change_role(GROUP_ADMIN, USER, Some(Role::Banned)).await,
+            Err(RoleChangeError::NotBotAdmin)
+        );
+        assert_eq!(
+            acl.role_of(USER, -200, ChatType::Supergroup).await,
+            Role::User
+        );
+    }
+
+    #[tokio::test]
+    async fn admins_cannot_undo_what_owners_did() {
+        let acl = acl().await;
+        acl.change_role(OWNER, USER, Some(Role::Banned))
+            .await
+            .unwrap();
+        assert_eq!(
+            acl.change_role(ADMIN, USER, None).await,
+            Err(RoleChangeError::OwnersOnlyUser)
+        );
+        assert_eq!(
+            acl.change_role(ADMIN, OWNER, Some(Role::Banned)).await,
+            Err(RoleChangeError::OwnersOnlyUser)
+        );
+        assert_eq!(
+            acl.change_role(ADMIN, 10, Some(Role::Admin)).await,
+            Err(RoleChangeError::OwnersOnlyRole(Role::Admin))
+        );
+        // A ban set by an admin can be lifted by another admin.
+        acl.change_role(ADMIN, 10, Some(Role::Banned))
+            .await
+            .unwrap();
+        acl.set_role(4, Role::Admin).await;
+        assert!(acl.change_role(4, 10, None).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn keeps_assigned_roles_across_restarts() {
+        let storage: Arc<dyn Storage> = Arc::new(MemoryStorage::default());
+        let acl = acl().await;
+        acl.load(storage.clone()).await;
+        acl.change_role(OWNER, USER, Some(Role::Banned))
+            .await
+            .unwrap();
+        acl.change_role(OWNER, 10, Some(Role::Banned))
+            .await
+            .unwrap();
+        acl.change_role(OWNER, 10, None).await.unwrap();
+
+        let restarted = Acl::new(AclConfig::default());
+        restarted.load(storage).await;
+        assert_eq!(
+            restarted.role_of(USER, USER, ChatType::Private).await,
+            Role::Banned
+        );
+        assert_eq!(
+            restarted.role_of(10, 10, ChatType::Private).await,
+            Role::User
+        );
+    }
+
+    #[tokio::test]
+    async fn admins_and_owners_skip_cooldowns() {
+        let acl = acl().await;
+        acl.set_role(4, Role::Banned).await;
+        for (user, skips) in [(OWNER, true), (ADMIN, true), (USER, false), (4, false)] {
+            let message = message("/echo").from(user).in_group(-200).build();
+            assert_eq!(acl.skips_cooldowns(&message).await, skips, "user {user}");
+        }
+    }
+}
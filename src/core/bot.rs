@@ -1,29 +1,64 @@
 use crate::core::commands::CommandRegistry;
+use crate::core::replies::ReplyLog;
+use crate::core::token::Token;
+use crate::i18n::I18n;
+use crate::metrics::Metrics;
+use crate::storage::Storage;
 use std::sync::atomic::AtomicI64;
 use std::sync::Arc;
 
+/// Largest upload accepted by the cloud Bot API.
+const CLOUD_UPLOAD_LIMIT: u64 = 50 * 1024 * 1024;
+/// Largest upload accepted by a local Bot API server.
+const LOCAL_UPLOAD_LIMIT: u64 = 2000 * 1024 * 1024;
+
 pub struct Bot {
-    pub token: String,
+    /// Name of the bot in the supervisor config, tells handlers shared by
+    /// several bots where the update came from.
+    pub name: String,
+    pub token: Token,
     pub offset: AtomicI64,
     pub polling_timeout: i64,
     pub base_url: String,
+    /// `base_url` points to a self-hosted `telegram-bot-api` server running
+    /// with `--local`: bigger uploads, files are read from its disk.
+    pub local_mode: bool,
     pub handlers: Arc<dyn CommandRegistry>,
+    /// Collected only when the metrics endpoint is enabled.
+    pub metrics: Option<Arc<Metrics>>,
+    /// Persistent state shared by handlers.
+    pub storage: Arc<dyn Storage>,
+    pub i18n: Arc<I18n>,
+    /// Recent replies of the bot, handed to handlers of edited commands.
+    pub replies: ReplyLog,
 }
 
 impl Bot {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
-        token: String,
+        name: String,
+        token: Token,
         offset: AtomicI64,
         polling_timeout: i64,
         base_url: String,
+        local_mode: bool,
         handlers: Arc<dyn CommandRegistry>,
+        metrics: Option<Arc<Metrics>>,
+        storage: Arc<dyn Storage>,
+        i18n: Arc<I18n>,
     ) -> Self {
         Self {
+            name,
             token,
             offset,
             polling_timeout,
             base_url,
+            local_mode,
             handlers,
+            metrics,
+            storage,
+            i18n,
+            replies: ReplyLog::new(),
         }
     }
 
@@ -34,7 +69,32 @@ impl Bot {
         }
     }
 
+    /// URL of the API method. Contains the token, so it must not be logged;
+    /// use `display_url` for that.
     pub fn url(&self, method: &str) -> String {
+        format!("{}/bot{}/{method}", self.base_url, self.token.expose())
+    }
+
+    /// URL to download a file from the cloud API. Contains the token.
+    pub fn file_url(&self, file_path: &str) -> String {
+        format!(
+            "{}/file/bot{}/{file_path}",
+            self.base_url,
+            self.token.expose()
+        )
+    }
+
+    /// Largest file that can be uploaded, in bytes.
+    pub fn upload_limit(&self) -> u64 {
+        if self.local_mode {
+            LOCAL_UPLOAD_LIMIT
+        } else {
+            CLOUD_UPLOAD_LIMIT
+        }
+    }
+
+    /// URL of the API method with the token masked.
+    pub fn display_url(&self, method: &str) -> String {
         format!("{}/bot{}/{method}", self.base_url, self.token)
     }
 }
@@ -1,23 +1,218 @@
+use crate::core::acl::{Acl, Denial, DenialHandler};
 use crate::core::bot::Bot;
 use crate::core::commands::BotCommand;
 use crate::core::commands::Command;
 use crate::core::commands::CommandHandler;
 use crate::core::commands::CommandRegistry;
+use crate::core::commands::{EditPolicy, EditedCommand};
+use crate::core::cooldown::{CooldownCheck, Cooldowns};
+use crate::core::events::{Event, EventHandler, EventKind, MESSAGE_UPDATES};
+use crate::core::failures::{guard, ErrorHandler, HandlerFailure, DEFAULT_HANDLER_TIMEOUT};
+use crate::types::Message;
 use async_trait::async_trait;
 use std::collections::HashMap;
+use std::future::Future;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
 
+/// Command and arguments a reply keyboard button stands for.
+type ButtonAction = (BotCommand, Vec<String>);
+
+/// What a handler runs for, commands and events are measured separately.
+#[derive(Debug, Clone, Copy)]
+enum Handled {
+    Command(&'static str),
+    Event(&'static str),
+}
+
+impl Handled {
+    fn name(self) -> &'static str {
+        match self {
+            Handled::Command(name) | Handled::Event(name) => name,
+        }
+    }
+}
+
 pub struct Registry {
     handlers: RwLock<HashMap<BotCommand, Arc<dyn CommandHandler>>>,
+    buttons: RwLock<HashMap<String, ButtonAction>>,
+    edit_policies: RwLock<HashMap<BotCommand, EditPolicy>>,
+    event_handlers: RwLock<HashMap<EventKind, Vec<Arc<dyn EventHandler>>>>,
+    acl: Option<Arc<Acl>>,
+    denial_handler: RwLock<Option<Arc<dyn DenialHandler>>>,
+    cooldowns: Cooldowns,
+    error_handler: RwLock<Option<Arc<dyn ErrorHandler>>>,
+    handler_timeout: RwLock<Duration>,
 }
 
 impl Registry {
     pub fn new() -> Arc<Self> {
         Arc::new(Self {
             handlers: RwLock::new(HashMap::new()),
+            buttons: RwLock::new(HashMap::new()),
+            edit_policies: RwLock::new(HashMap::new()),
+            event_handlers: RwLock::new(HashMap::new()),
+            acl: None,
+            denial_handler: RwLock::new(None),
+            cooldowns: Cooldowns::new(),
+            error_handler: RwLock::new(None),
+            handler_timeout: RwLock::new(DEFAULT_HANDLER_TIMEOUT),
         })
     }
+
+    /// Creates a registry that checks every command against the access list.
+    pub fn with_acl(acl: Arc<Acl>) -> Arc<Self> {
+        Arc::new(Self {
+            handlers: RwLock::new(HashMap::new()),
+            buttons: RwLock::new(HashMap::new()),
+            edit_policies: RwLock::new(HashMap::new()),
+            event_handlers: RwLock::new(HashMap::new()),
+            acl: Some(acl),
+            denial_handler: RwLock::new(None),
+            cooldowns: Cooldowns::new(),
+            error_handler: RwLock::new(None),
+            handler_timeout: RwLock::new(DEFAULT_HANDLER_TIMEOUT),
+        })
+    }
+
+    /// Sets the handler that answers commands rejected by the access list.
+    pub async fn set_denial_handler(&self, handler: Arc<dyn DenialHandler>) {
+        *self.denial_handler.write().await = Some(handler);
+    }
+
+    /// Sets the hook called when a handler panics or times out.
+    pub async fn set_error_handler(&self, handler: Arc<dyn ErrorHandler>) {
+        *self.error_handler.write().await = Some(handler);
+    }
+
+    /// Sets how long handlers may run unless they set their own timeout.
+    pub async fn set_handler_timeout(&self, timeout: Duration) {
+        *self.handler_timeout.write().await = timeout;
+    }
+
+    /// Checks the command against the access list and answers denials.
+    async fn permitted(&self, ctx: &Arc<Bot>, command: &Command) -> bool {
+        let Some(acl) = &self.acl else {
+            return true;
+        };
+        let Err(denial) = acl.check(command).await else {
+            return true;
+        };
+        println!("Command {:?} denied: {:?}", command.command, denial);
+        self.deny(ctx, command, denial).await;
+        false
+    }
+
+    /// Counts a use of the command against its cooldown. Tells the user
+    /// when to retry, once per cooldown.
+    async fn cooled_down(
+        &self,
+        ctx: &Arc<Bot>,
+        command: &Command,
+        handler: &Arc<dyn CommandHandler>,
+    ) -> bool {
+        let cooldown = match &self.acl {
+            Some(acl) => acl.cooldown(command.command).await,
+            None => None,
+        };
+        let Some(cooldown) = cooldown.or_else(|| handler.cooldown()) else {
+            return true;
+        };
+        if let Some(acl) = &self.acl {
+            if acl.skips_cooldowns(&command.message).await {
+                return true;
+            }
+        }
+        let CooldownCheck::Limited { retry_in, notify } =
+            self.cooldowns.check(&ctx.storage, &cooldown, command).await
+        else {
+            return true;
+        };
+        println!("Command {:?} on cooldown for {retry_in:?}", command.command);
+        if notify {
+            // Round up, "try again in 0 seconds" would be confusing.
+            let retry_in = retry_in.as_secs() + u64::from(retry_in.subsec_nanos() > 0);
+            self.deny(ctx, command, Denial::Cooldown { retry_in }).await;
+        }
+        false
+    }
+
+    /// Runs a handler with a timeout, records its duration and reports
+    /// panics and timeouts to the error handler.
+    async fn run<F>(
+        &self,
+        ctx: &Arc<Bot>,
+        handled: Handled,
+        update: String,
+        message: Option<Message>,
+        timeout: Option<Duration>,
+        handler: F,
+    ) where
+        F: Future<Output = ()> + Send,
+    {
+        let timeout = match timeout {
+            Some(timeout) => timeout,
+            None => *self.handler_timeout.read().await,
+        };
+        let started = Instant::now();
+        let result = guard(handler, timeout).await;
+        if let Some(metrics) = &ctx.metrics {
+            match handled {
+                Handled::Command(name) => {
+                    metrics.record_command(&ctx.name, name, started.elapsed())
+                }
+                Handled::Event(name) => metrics.record_event(&ctx.name, name, started.elapsed()),
+            }
+        }
+        let Err(error) = result else {
+            return;
+        };
+        if let Some(metrics) = &ctx.metrics {
+            match handled {
+                Handled::Command(name) => metrics.record_command_failure(&ctx.name, name),
+                Handled::Event(name) => metrics.record_event_failure(&ctx.name, name),
+            }
+        }
+        let label = handled.name();
+        let failure = HandlerFailure {
+            handler: label,
+            update,
+            message,
+            error,
+        };
+        println!("{failure}");
+        let Some(error_handler) = self.error_handler.read().await.clone() else {
+            return;
+        };
+        // The hook must not take the worker down either.
+        let report = error_handler.failed(ctx.clone(), failure);
+        if let Err(error) = guard(report, timeout).await {
+            println!("Error handler {error} handling a failure of {label}");
+        }
+    }
+
+    async fn deny(&self, ctx: &Arc<Bot>, command: &Command, denial: Denial) {
+        if let Some(handler) = self.denial_handler.read().await.clone() {
+            handler.denied(ctx.clone(), command.clone(), denial).await;
+        }
+    }
+}
+
+/// Commands the owner of a business account sends in their business chats
+/// only reach handlers that opt in.
+fn accepts_sender(command: &Command, handler: Option<&Arc<dyn CommandHandler>>) -> bool {
+    if !command.message.is_from_business_owner() {
+        return true;
+    }
+    let accepted = handler.is_some_and(|handler| handler.handles_business_owner());
+    if !accepted {
+        println!(
+            "Ignoring {} sent by the business account owner",
+            command.summary()
+        );
+    }
+    accepted
 }
 
 #[async_trait]
@@ -27,10 +222,147 @@ impl CommandRegistry for Registry {
     }
 
     async fn dispatch(&self, ctx: Arc<Bot>, command: Command) {
-        if let Some(handler) = self.handlers.read().await.get(&command.command) {
-            handler.handle(ctx, command).await;
+        let handler = self.handlers.read().await.get(&command.command).cloned();
+        if !accepts_sender(&command, handler.as_ref()) {
+            return;
+        }
+        if !self.permitted(&ctx, &command).await {
+            return;
+        }
+        let Some(handler) = handler else {
+            // ctx.send_message("Command not found").await;
+            return;
+        };
+        if !self.cooled_down(&ctx, &command, &handler).await {
+            return;
+        }
+        let update = command.summary();
+        let message = Some(command.message.clone());
+        self.run(
+            &ctx,
+            Handled::Command(command.command.name()),
+            update,
+            message,
+            handler.timeout(),
+            handler.handle(ctx.clone(), command),
+        )
+        .await;
+    }
+
+    async fn set_edit_policy(&self, name: BotCommand, policy: EditPolicy) {
+        self.edit_policies.write().await.insert(name, policy);
+    }
+
+    async fn dispatch_edited(&self, ctx: Arc<Bot>, command: Command) {
+        let Some(handler) = self.handlers.read().await.get(&command.command).cloned() else {
+            return;
+        };
+        if !accepts_sender(&command, Some(&handler)) {
             return;
         }
-        // ctx.send_message("Command not found").await;
+        let policy = match self.edit_policies.read().await.get(&command.command) {
+            Some(policy) => *policy,
+            None => handler.edit_policy(),
+        };
+        match policy {
+            EditPolicy::Ignore => {
+                println!("Ignoring edited command {:?}", command.command);
+            }
+            EditPolicy::Rerun => self.dispatch(ctx, command).await,
+            EditPolicy::Deliver => {
+                if !self.permitted(&ctx, &command).await
+                    || !self.cooled_down(&ctx, &command, &handler).await
+                {
+                    return;
+                }
+                let handled = Handled::Command(command.command.name());
+                let update = format!("edited {}", command.summary());
+                let message = Some(command.message.clone());
+                let previous_reply = ctx.replies.reply_to(&command.message);
+                self.run(
+                    &ctx,
+                    handled,
+                    update,
+                    message,
+                    handler.timeout(),
+                    handler.handle_edited(
+                        ctx.clone(),
+                        EditedCommand {
+                            command,
+                            previous_reply,
+                        },
+                    ),
+                )
+                .await;
+            }
+        }
+    }
+
+    async fn register_event(&self, kind: EventKind, handler: Arc<dyn EventHandler>) {
+        self.event_handlers
+            .write()
+            .await
+            .entry(kind)
+            .or_default()
+            .push(handler);
+    }
+
+    async fn dispatch_event(&self, ctx: Arc<Bot>, event: Event) {
+        let handlers = match self.event_handlers.read().await.get(&event.kind()) {
+            Some(handlers) => handlers.clone(),
+            None => return,
+        };
+        let handled = Handled::Event(event.kind().name());
+        for handler in handlers {
+            self.run(
+                &ctx,
+                handled,
+                event.summary(),
+                event.message().cloned(),
+                handler.timeout(),
+                handler.handle_event(ctx.clone(), event.clone()),
+            )
+            .await;
+        }
+    }
+
+    async fn register_button(&self, text: &str, command: BotCommand, args: &[&str]) {
+        let args = args.iter().map(|arg| arg.to_string()).collect();
+        self.buttons
+            .write()
+            .await
+            .insert(text.trim().to_string(), (command, args));
+    }
+
+    async fn dispatch_text(&self, ctx: Arc<Bot>, message: Message) {
+        let Some(text) = message.text.as_deref() else {
+            return;
+        };
+        let Some((command, args)) = self.buttons.read().await.get(text.trim()).cloned() else {
+            return;
+        };
+        // Goes through the same access checks and metrics as a typed command.
+        self.dispatch(
+            ctx,
+            Command {
+                command,
+                args,
+                message,
+            },
+        )
+        .await;
+    }
+
+    async fn allowed_updates(&self) -> Vec<String> {
+        let mut update_types: Vec<&str> = Vec::new();
+        if !self.handlers.read().await.is_empty() || !self.buttons.read().await.is_empty() {
+            update_types.extend(MESSAGE_UPDATES);
+        }
+        for kind in self.event_handlers.read().await.keys() {
+            update_types.extend(kind.update_types());
+        }
+        update_types.sort_unstable();
+        update_types.dedup();
+        update_types.into_iter().map(String::from).collect()
     }
 }
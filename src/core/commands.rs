@@ -1,66 +1,143 @@
 use crate::core::bot::Bot;
+use crate::core::cooldown::Cooldown;
+use crate::core::events::{Event, EventHandler, EventKind};
 use crate::types::{EntityType, Message};
 use async_trait::async_trait;
 use std::sync::Arc;
+use std::time::Duration;
 
 /// Enum for all possible bot commands
-#[derive(Debug, Eq, Hash, PartialEq)]
+#[derive(Debug, Clone, Copy, Eq, Hash, PartialEq)]
 pub enum BotCommand {
     Video,
     Echo,
+    Grant,
+    Revoke,
+    Remind,
+    Every,
+    Jobs,
+    CancelJob,
+    Language,
+    ChatLanguage,
+    Poll,
+    Results,
+    Donate,
     Unknown,
 }
 
+impl BotCommand {
+    /// All known commands.
+    pub const ALL: [BotCommand; 13] = [
+        BotCommand::Video,
+        BotCommand::Echo,
+        BotCommand::Grant,
+        BotCommand::Revoke,
+        BotCommand::Remind,
+        BotCommand::Every,
+        BotCommand::Jobs,
+        BotCommand::CancelJob,
+        BotCommand::Language,
+        BotCommand::ChatLanguage,
+        BotCommand::Poll,
+        BotCommand::Results,
+        BotCommand::Donate,
+    ];
+
+    /// Name of the command as typed by users, without the leading slash.
+    pub fn name(&self) -> &'static str {
+        match self {
+            BotCommand::Video => "video",
+            BotCommand::Echo => "echo",
+            BotCommand::Grant => "grant",
+            BotCommand::Revoke => "revoke",
+            BotCommand::Remind => "remind",
+            BotCommand::Every => "every",
+            BotCommand::Jobs => "jobs",
+            BotCommand::CancelJob => "canceljob",
+            BotCommand::Language => "language",
+            BotCommand::ChatLanguage => "chatlanguage",
+            BotCommand::Poll => "poll",
+            BotCommand::Results => "results",
+            BotCommand::Donate => "donate",
+            BotCommand::Unknown => "unknown",
+        }
+    }
+
+    /// Looks up a command by its name, without the leading slash.
+    pub fn from_name(name: &str) -> BotCommand {
+        let name = name.to_lowercase();
+        Self::ALL
+            .into_iter()
+            .find(|command| command.name() == name)
+            .unwrap_or(BotCommand::Unknown)
+    }
+}
+
 /// Wrapper for a command that is known to the bot and its arguments
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Command {
     pub command: BotCommand,
     pub args: Vec<String>,
     pub message: Message,
 }
 
+impl Command {
+    /// Short description for logs, e.g. `/echo from 42 in chat -100`.
+    pub fn summary(&self) -> String {
+        let sender = match &self.message.from.0 {
+            Some(user) => user.id.to_string(),
+            None => String::from("unknown"),
+        };
+        format!(
+            "/{} from {sender} in chat {}",
+            self.command.name(),
+            self.message.chat.id()
+        )
+    }
+}
+
 /// Decides which command to execute based on the message content and its arguments
 /// Consumes the message
 pub fn decide_command(message: Message) -> Option<Command> {
-    let entities = message.entities.as_ref()?;
+    let entities = message.entities_or_caption_entities()?;
     let mut command = BotCommand::Unknown;
     let mut args = Vec::new();
     for entity in entities {
         match entity.entity_type {
-            EntityType::BotCommand => {
-                let start: usize = entity.offset as usize;
-                let end: usize = start + entity.length as usize;
-                match &message.text {
-                    Some(text) => {
-                        let cmd = text[start..end].trim_start_matches("/").to_lowercase();
-                        command = match cmd.as_str() {
-                            "video" => BotCommand::Video,
-                            "echo" => BotCommand::Echo,
-
-                            _ => {
-                                println!("Unknown command: <{}>", cmd);
-                                BotCommand::Unknown
-                            }
-                        };
-                    }
-                    None => {
-                        println!("Received command without text");
+            EntityType::BotCommand => match message.text_or_caption() {
+                Some(text) => {
+                    let Some(range) = entity.byte_range(text) else {
+                        println!("Ignoring {entity} outside of the text");
+                        continue;
+                    };
+                    let cmd = text[range.clone()].trim_start_matches("/");
+                    // Drop the bot mention from `/command@bot_name`
+                    let cmd = cmd.split('@').next().unwrap_or(cmd);
+                    command = BotCommand::from_name(cmd);
+                    if command == BotCommand::Unknown {
+                        println!("Unknown command: <{}>", cmd);
                     }
+                    args.extend(text[range.end..].split_whitespace().map(String::from));
                 }
-            }
-            EntityType::Url => {
-                let start: usize = entity.offset as usize;
-                let end: usize = start + entity.length as usize;
-                match &message.text {
-                    Some(text) => {
-                        let url = text[start..end].to_string();
+                None => {
+                    println!("Received command without text");
+                }
+            },
+            EntityType::Url => match message.text_or_caption() {
+                Some(text) => {
+                    let Some(range) = entity.byte_range(text) else {
+                        println!("Ignoring {entity} outside of the text");
+                        continue;
+                    };
+                    let url = text[range].to_string();
+                    if !args.contains(&url) {
                         args.push(url);
                     }
-                    None => {
-                        println!("Received URL without text");
-                    }
                 }
-            }
+                None => {
+                    println!("Received URL without text");
+                }
+            },
             _ => {
                 println!(
                     "Received message without command: {:#?}",
@@ -82,13 +159,115 @@ pub fn decide_command(message: Message) -> Option<Command> {
     }
 }
 
+/// What happens when the user edits a message with a command.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum EditPolicy {
+    /// Do nothing, e.g. for commands with side effects
+    #[default]
+    Ignore,
+    /// Handle the edited command like a new one
+    Rerun,
+    /// Call `CommandHandler::handle_edited` with the bot's previous reply
+    Deliver,
+}
+
+/// A command whose message was edited, with the bot's latest reply to the
+/// original message, if it's still known.
+#[derive(Debug, Clone)]
+pub struct EditedCommand {
+    pub command: Command,
+    pub previous_reply: Option<Message>,
+}
+
 #[async_trait]
 pub trait CommandHandler: Send + Sync {
     async fn handle(&self, ctx: Arc<Bot>, command: Command);
+
+    /// How edits of the command's message are handled, unless the registry
+    /// overrides it.
+    fn edit_policy(&self) -> EditPolicy {
+        EditPolicy::Ignore
+    }
+
+    /// How often the command may be used, unless the ACL config overrides
+    /// it. Admins are not limited.
+    fn cooldown(&self) -> Option<Cooldown> {
+        None
+    }
+
+    /// Time after which the handler is cancelled, instead of the registry's default.
+    fn timeout(&self) -> Option<Duration> {
+        None
+    }
+
+    /// Whether the owner of a connected business account may run the command
+    /// from their own business chats. Off by default, so the bot only answers
+    /// the owner's customers.
+    fn handles_business_owner(&self) -> bool {
+        false
+    }
+
+    /// Handles an edited command under `EditPolicy::Deliver`. Handles it as
+    /// a new command by default.
+    async fn handle_edited(&self, ctx: Arc<Bot>, edited: EditedCommand) {
+        self.handle(ctx, edited.command).await;
+    }
 }
 
 #[async_trait]
 pub trait CommandRegistry: Send + Sync {
     async fn register(&self, name: BotCommand, handler: Arc<dyn CommandHandler>);
     async fn dispatch(&self, ctx: Arc<Bot>, command: Command);
+    /// Overrides the edit policy of the command's handler.
+    async fn set_edit_policy(&self, name: BotCommand, policy: EditPolicy);
+    /// Dispatches a command from an edited message according to the edit policy.
+    async fn dispatch_edited(&self, ctx: Arc<Bot>, command: Command);
+    /// Adds a handler for an event kind. Every handler of the kind receives the event.
+    async fn register_event(&self, kind: EventKind, handler: Arc<dyn EventHandler>);
+    async fn dispatch_event(&self, ctx: Arc<Bot>, event: Event);
+    /// Runs `command` with `args` when a user sends `text`, i.e. presses a
+    /// reply keyboard button with that text.
+    async fn register_button(&self, text: &str, command: BotCommand, args: &[&str]);
+    /// Dispatches a text message if it matches a registered button.
+    async fn dispatch_text(&self, ctx: Arc<Bot>, message: Message);
+    /// Update types the registered handlers need, for `allowed_updates`.
+    async fn allowed_updates(&self) -> Vec<String>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decide_command, BotCommand};
+    use crate::types::fixtures::message;
+
+    #[test]
+    fn counts_offsets_in_utf16_units() {
+        let message = message("привет 👍 /echo hi")
+            .entity("bot_command", "/echo")
+            .build();
+        let command = decide_command(message).unwrap();
+        assert_eq!(command.command, BotCommand::Echo);
+        assert_eq!(command.args, ["hi"]);
+    }
+
+    #[test]
+    fn reads_urls_from_non_ascii_captions() {
+        let message = message("Фото /echo https://example.com")
+            .entity("bot_command", "/echo")
+            .entity("url", "https://example.com")
+            .as_caption()
+            .build();
+        let command = decide_command(message).unwrap();
+        assert_eq!(command.args, ["https://example.com"]);
+    }
+
+    #[test]
+    fn ignores_entities_outside_the_text() {
+        // Past the end, and splitting the surrogate pair of the emoji.
+        for (offset, length) in [(40, 5), (1, 5), (0, 1)] {
+            let message = message("👍/echo")
+                .raw_entity("bot_command", offset, length)
+                .build();
+            assert!(decide_command(message).is_none(), "{offset}+{length}");
+        }
+    }
 }
@@ -0,0 +1,256 @@
+use crate::core::commands::{BotCommand, Command};
+use crate::storage::{Namespace, Storage};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+/// Prefix of the storage keys holding cooldown state, followed by the command name.
+const COOLDOWN_KEY_PREFIX: &str = "cooldown.";
+
+/// Who shares a cooldown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CooldownScope {
+    /// Each user separately, across all chats
+    User,
+    /// Everyone in a chat together
+    Chat,
+    /// Everyone everywhere together
+    Global,
+}
+
+/// Allows `burst` uses of a command per `window` seconds, e.g. in the ACL
+/// config: `"video": { "cooldown": { "scope": "user", "window": 60, "burst": 2 } }`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Cooldown {
+    pub scope: CooldownScope,
+    /// Length of the window in seconds.
+    pub window: u64,
+    /// Uses allowed within a window.
+    #[serde(default = "Cooldown::default_burst")]
+    pub burst: u32,
+}
+
+impl Cooldown {
+    fn default_burst() -> u32 {
+        1
+    }
+
+    pub fn per_user(window: u64) -> Self {
+        Self {
+            scope: CooldownScope::User,
+            window,
+            burst: 1,
+        }
+    }
+
+    pub fn per_chat(window: u64) -> Self {
+        Self {
+            scope: CooldownScope::Chat,
+            window,
+            burst: 1,
+        }
+    }
+
+    pub fn global(window: u64) -> Self {
+        Self {
+            scope: CooldownScope::Global,
+            window,
+            burst: 1,
+        }
+    }
+
+    pub fn burst(mut self, burst: u32) -> Self {
+        self.burst = burst.max(1);
+        self
+    }
+
+    /// Where the state of the cooldown is stored for the command.
+    fn namespace(&self, command: &Command) -> Namespace {
+        match self.scope {
+            CooldownScope::User => match &command.message.from.0 {
+                Some(user) => Namespace::User(user.id),
+                // Anonymous admins and channels act for the chat.
+                None => Namespace::Chat(command.message.chat.id()),
+            },
+            CooldownScope::Chat => Namespace::Chat(command.message.chat.id()),
+            CooldownScope::Global => Namespace::Global,
+        }
+    }
+}
+
+/// Recent uses of a command within one scope.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct CooldownState {
+    /// Unix times of the uses in the current window, in milliseconds.
+    uses: Vec<i64>,
+    /// The user was already told to wait, don't repeat it until the next use.
+    notified: bool,
+}
+
+impl CooldownState {
+    /// Records a use at `now` if fewer than `burst` uses happened within
+    /// the last `window` milliseconds. A burst of 0 counts as 1, so a
+    /// misconfigured cooldown can't block the command forever.
+    fn record(&mut self, now: i64, window: i64, burst: u32) -> CooldownCheck {
+        self.uses.retain(|used| now - used < window);
+        if self.uses.len() < burst.max(1) as usize {
+            self.uses.push(now);
+            self.notified = false;
+            return CooldownCheck::Allowed;
+        }
+        let oldest = self.uses.iter().min().copied().unwrap_or(now);
+        let notify = !self.notified;
+        self.notified = true;
+        CooldownCheck::Limited {
+            retry_in: Duration::from_millis((oldest + window - now).max(0) as u64),
+            notify,
+        }
+    }
+}
+
+/// Result of a cooldown check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CooldownCheck {
+    Allowed,
+    /// `notify` is false if the user was already told to wait.
+    Limited {
+        retry_in: Duration,
+        notify: bool,
+    },
+}
+
+/// Counts uses of commands in storage. Uses are recorded with an atomic
+/// storage update, so bot instances sharing an SQLite database share the
+/// cooldowns.
+#[derive(Default)]
+pub struct Cooldowns;
+
+impl Cooldowns {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn key(command: BotCommand) -> String {
+        format!("{COOLDOWN_KEY_PREFIX}{}", command.name())
+    }
+
+    /// Records a use of the command if the cooldown allows it.
+    pub async fn check(
+        &self,
+        storage: &Arc<dyn Storage>,
+        cooldown: &Cooldown,
+        command: &Command,
+    ) -> CooldownCheck {
+        let ns = cooldown.namespace(command);
+        let key = Self::key(command.command);
+        let now = now_millis();
+        let window = cooldown.window.saturating_mul(1000).min(i64::MAX as u64) as i64;
+        let burst = cooldown.burst;
+        storage
+            .update(&ns, &key, move |state: &mut CooldownState| {
+                state.record(now, window, burst)
+            })
+            .await
+            // Don't lock users out of commands because the storage failed.
+            .unwrap_or(CooldownCheck::Allowed)
+    }
+}
+
+fn now_millis() -> i64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|now| now.as_millis() as i64)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Cooldown, CooldownCheck, CooldownState, Cooldowns};
+    use crate::core::commands::{BotCommand, Command};
+    use crate::storage::{MemoryStorage, Storage};
+    use crate::types::fixtures::message;
+    use serde_json::json;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    fn limited(retry_in: u64, notify: bool) -> CooldownCheck {
+        CooldownCheck::Limited {
+            retry_in: Duration::from_millis(retry_in),
+            notify,
+        }
+    }
+
+    fn command(user: i64) -> Command {
+        Command {
+            command: BotCommand::Echo,
+            args: Vec::new(),
+            message: message("/echo").from(user).in_group(-100).build(),
+        }
+    }
+
+    #[test]
+    fn allows_again_after_the_window() {
+        let mut state = CooldownState::default();
+        assert_eq!(state.record(0, 1000, 1), CooldownCheck::Allowed);
+        assert_eq!(state.record(400, 1000, 1), limited(600, true));
+        assert_eq!(state.record(1000, 1000, 1), CooldownCheck::Allowed);
+    }
+
+    #[test]
+    fn allows_a_burst_within_the_window() {
+        let mut state = CooldownState::default();
+        assert_eq!(state.record(0, 1000, 3), CooldownCheck::Allowed);
+        assert_eq!(state.record(100, 1000, 3), CooldownCheck::Allowed);
+        assert_eq!(state.record(200, 1000, 3), CooldownCheck::Allowed);
+        assert_eq!(state.record(300, 1000, 3), limited(700, true));
+        // The oldest use expires first and frees one slot.
+        assert_eq!(state.record(1000, 1000, 3), CooldownCheck::Allowed);
+        assert_eq!(state.record(1050, 1000, 3), limited(50, true));
+    }
+
+    #[test]
+    fn tells_to_wait_once_per_cooldown() {
+        let mut state = CooldownState::default();
+        state.record(0, 1000, 1);
+        assert_eq!(state.record(100, 1000, 1), limited(900, true));
+        assert_eq!(state.record(200, 1000, 1), limited(800, false));
+        assert_eq!(state.record(1000, 1000, 1), CooldownCheck::Allowed);
+        assert_eq!(state.record(1100, 1000, 1), limited(900, true));
+    }
+
+    #[test]
+    fn treats_a_zero_burst_as_one() {
+        let cooldown: Cooldown =
+            serde_json::from_value(json!({ "scope": "user", "window": 1, "burst": 0 })).unwrap();
+        let mut state = CooldownState::default();
+        assert_eq!(
+            state.record(0, 1000, cooldown.burst),
+            CooldownCheck::Allowed
+        );
+        assert_eq!(
+            state.record(1000, 1000, cooldown.burst),
+            CooldownCheck::Allowed
+        );
+    }
+
+    #[tokio::test]
+    async fn counts_uses_per_user_in_storage() {
+        let storage: Arc<dyn Storage> = Arc::new(MemoryStorage::default());
+        let cooldowns = Cooldowns::new();
+        let cooldown = Cooldown::per_user(60);
+        let (first, second) = (command(1), command(2));
+        assert_eq!(
+            cooldowns.check(&storage, &cooldown, &first).await,
+            CooldownCheck::Allowed
+        );
+        assert!(matches!(
+            cooldowns.check(&storage, &cooldown, &first).await,
+            CooldownCheck::Limited { notify: true, .. }
+        ));
+        assert_eq!(
+            cooldowns.check(&storage, &cooldown, &second).await,
+            CooldownCheck::Allowed
+        );
+    }
+}
@@ -0,0 +1,312 @@
+use crate::core::bot::Bot;
+use crate::core::commands::{decide_command, Command};
+use crate::types::{
+    BusinessConnection, BusinessMessagesDeleted, ChatJoinRequest, ChatMemberUpdated, EntityType,
+    Message, Poll, PollAnswer, PreCheckoutQuery, ShippingQuery, Update,
+};
+use async_trait::async_trait;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Update types that carry messages routed to commands and service message events.
+pub const MESSAGE_UPDATES: [&str; 4] = [
+    "message",
+    "edited_message",
+    "business_message",
+    "edited_business_message",
+];
+
+/// Kinds of non-command updates handlers can subscribe to.
+#[derive(Debug, Clone, Copy, Eq, Hash, PartialEq)]
+pub enum EventKind {
+    /// A member of a chat the bot administers changed status
+    ChatMember,
+    /// The bot itself was added, removed, promoted or restricted
+    MyChatMember,
+    ChatJoinRequest,
+    /// A poll sent by the bot changed state
+    Poll,
+    /// A user voted in a non-anonymous poll sent by the bot
+    PollAnswer,
+    ShippingQuery,
+    PreCheckoutQuery,
+    /// Service message about a successful payment
+    SuccessfulPayment,
+    /// Service message about a refunded payment
+    RefundedPayment,
+    /// Forum topic created, edited, closed or reopened, General topic hidden or unhidden
+    ForumTopic,
+    /// The bot was connected to or disconnected from a business account
+    BusinessConnection,
+    DeletedBusinessMessages,
+}
+
+impl EventKind {
+    /// Name used in logs and metrics.
+    pub fn name(&self) -> &'static str {
+        match self {
+            EventKind::ChatMember => "chat_member",
+            EventKind::MyChatMember => "my_chat_member",
+            EventKind::ChatJoinRequest => "chat_join_request",
+            EventKind::Poll => "poll",
+            EventKind::PollAnswer => "poll_answer",
+            EventKind::ShippingQuery => "shipping_query",
+            EventKind::PreCheckoutQuery => "pre_checkout_query",
+            EventKind::SuccessfulPayment => "successful_payment",
+            EventKind::RefundedPayment => "refunded_payment",
+            EventKind::ForumTopic => "forum_topic",
+            EventKind::BusinessConnection => "business_connection",
+            EventKind::DeletedBusinessMessages => "deleted_business_messages",
+        }
+    }
+
+    /// Update types Telegram must send for the event to arrive, as used in
+    /// `allowed_updates`. Service messages come inside message updates.
+    pub fn update_types(&self) -> &'static [&'static str] {
+        match self {
+            EventKind::SuccessfulPayment | EventKind::RefundedPayment | EventKind::ForumTopic => {
+                &MESSAGE_UPDATES
+            }
+            EventKind::ChatMember => &["chat_member"],
+            EventKind::MyChatMember => &["my_chat_member"],
+            EventKind::ChatJoinRequest => &["chat_join_request"],
+            EventKind::Poll => &["poll"],
+            EventKind::PollAnswer => &["poll_answer"],
+            EventKind::ShippingQuery => &["shipping_query"],
+            EventKind::PreCheckoutQuery => &["pre_checkout_query"],
+            EventKind::BusinessConnection => &["business_connection"],
+            EventKind::DeletedBusinessMessages => &["deleted_business_messages"],
+        }
+    }
+}
+
+/// A typed update that is not a command.
+#[derive(Debug, Clone)]
+pub enum Event {
+    ChatMember(ChatMemberUpdated),
+    MyChatMember(ChatMemberUpdated),
+    ChatJoinRequest(ChatJoinRequest),
+    Poll(Poll),
+    PollAnswer(PollAnswer),
+    ShippingQuery(ShippingQuery),
+    PreCheckoutQuery(PreCheckoutQuery),
+    /// The message carries a `successful_payment`
+    SuccessfulPayment(Box<Message>),
+    /// The message carries a `refunded_payment`
+    RefundedPayment(Box<Message>),
+    /// The message carries a forum service message, see `Message::forum_topic_event`
+    ForumTopic(Box<Message>),
+    BusinessConnection(BusinessConnection),
+    DeletedBusinessMessages(BusinessMessagesDeleted),
+}
+
+impl Event {
+    pub fn kind(&self) -> EventKind {
+        match self {
+            Event::ChatMember(_) => EventKind::ChatMember,
+            Event::MyChatMember(_) => EventKind::MyChatMember,
+            Event::ChatJoinRequest(_) => EventKind::ChatJoinRequest,
+            Event::Poll(_) => EventKind::Poll,
+            Event::PollAnswer(_) => EventKind::PollAnswer,
+            Event::ShippingQuery(_) => EventKind::ShippingQuery,
+            Event::PreCheckoutQuery(_) => EventKind::PreCheckoutQuery,
+            Event::SuccessfulPayment(_) => EventKind::SuccessfulPayment,
+            Event::RefundedPayment(_) => EventKind::RefundedPayment,
+            Event::ForumTopic(_) => EventKind::ForumTopic,
+            Event::BusinessConnection(_) => EventKind::BusinessConnection,
+            Event::DeletedBusinessMessages(_) => EventKind::DeletedBusinessMessages,
+        }
+    }
+
+    /// ID of the chat the event happened in, if it is bound to a chat.
+    pub fn chat_id(&self) -> Option<i64> {
+        match self {
+            Event::ChatMember(updated) | Event::MyChatMember(updated) => Some(updated.chat.id()),
+            Event::ChatJoinRequest(request) => Some(request.chat.id()),
+            Event::BusinessConnection(connection) => Some(connection.user_chat_id),
+            Event::DeletedBusinessMessages(deleted) => Some(deleted.chat.id()),
+            Event::SuccessfulPayment(message)
+            | Event::RefundedPayment(message)
+            | Event::ForumTopic(message) => Some(message.chat.id()),
+            Event::Poll(_)
+            | Event::PollAnswer(_)
+            | Event::ShippingQuery(_)
+            | Event::PreCheckoutQuery(_) => None,
+        }
+    }
+
+    /// The service message the event came in, if any.
+    pub fn message(&self) -> Option<&Message> {
+        match self {
+            Event::SuccessfulPayment(message)
+            | Event::RefundedPayment(message)
+            | Event::ForumTopic(message) => Some(message),
+            _ => None,
+        }
+    }
+
+    /// Short description for logs, e.g. `poll_answer` or `chat_member in chat -100`.
+    pub fn summary(&self) -> String {
+        match self.chat_id() {
+            Some(chat_id) => format!("{} in chat {chat_id}", self.kind().name()),
+            None => self.kind().name().to_string(),
+        }
+    }
+}
+
+/// Something the dispatcher has to route: a command, an event, or a text
+/// message that may be a press of a reply keyboard button.
+#[derive(Debug, Clone)]
+pub enum Incoming {
+    Command(Box<Command>),
+    Event(Box<Event>),
+    Text(Box<Message>),
+    /// A command from an edited message, handled by the edit policy
+    EditedCommand(Box<Command>),
+}
+
+impl Incoming {
+    /// Short description for logs.
+    pub fn summary(&self) -> String {
+        match self {
+            Incoming::Command(command) => command.summary(),
+            Incoming::Event(event) => event.summary(),
+            Incoming::Text(message) => format!("text in chat {}", message.chat.id()),
+            Incoming::EditedCommand(command) => format!("edited {}", command.summary()),
+        }
+    }
+}
+
+/// Turns an update into a command or an event. Returns `None` if nothing
+/// in the update is handled by the bot.
+pub fn route_update(update: Update) -> Option<Incoming> {
+    if let Some(updated) = update.chat_member {
+        return Some(Incoming::Event(Box::new(Event::ChatMember(updated))));
+    }
+    if let Some(updated) = update.my_chat_member {
+        return Some(Incoming::Event(Box::new(Event::MyChatMember(updated))));
+    }
+    if let Some(request) = update.chat_join_request {
+        return Some(Incoming::Event(Box::new(Event::ChatJoinRequest(request))));
+    }
+    if let Some(poll) = update.poll {
+        return Some(Incoming::Event(Box::new(Event::Poll(poll))));
+    }
+    if let Some(answer) = update.poll_answer {
+        return Some(Incoming::Event(Box::new(Event::PollAnswer(answer))));
+    }
+    if let Some(query) = update.shipping_query {
+        return Some(Incoming::Event(Box::new(Event::ShippingQuery(query))));
+    }
+    if let Some(query) = update.pre_checkout_query {
+        return Some(Incoming::Event(Box::new(Event::PreCheckoutQuery(query))));
+    }
+    if let Some(connection) = update.business_connection {
+        return Some(Incoming::Event(Box::new(Event::BusinessConnection(
+            connection,
+        ))));
+    }
+    if let Some(deleted) = update.deleted_business_messages {
+        return Some(Incoming::Event(Box::new(Event::DeletedBusinessMessages(
+            deleted,
+        ))));
+    }
+    // Business messages go through the same router as regular ones.
+    let edited = update.edited_message.is_some() || update.edited_business_message.is_some();
+    let message = update
+        .message
+        .or(update.edited_message)
+        .or(update.business_message)
+        .or(update.edited_business_message)?;
+    // Messages the bot sent on behalf of a business account come back as
+    // business messages; handling them could make the bot answer itself.
+    if message.sender_business_bot.is_some() {
+        return None;
+    }
+    if message.successful_payment().is_some() {
+        return Some(Incoming::Event(Box::new(Event::SuccessfulPayment(
+            Box::new(message),
+        ))));
+    }
+    if message.forum_topic_event().is_some() {
+        return Some(Incoming::Event(Box::new(Event::ForumTopic(Box::new(
+            message,
+        )))));
+    }
+    if message.refunded_payment().is_some() {
+        return Some(Incoming::Event(Box::new(Event::RefundedPayment(Box::new(
+            message,
+        )))));
+    }
+    let is_command = message
+        .entities_or_caption_entities()
+        .is_some_and(|entities| {
+            entities
+                .iter()
+                .any(|entity| matches!(entity.entity_type, EntityType::BotCommand))
+        });
+    if edited {
+        // Edits of plain text aren't button presses.
+        return decide_command(message).map(|command| Incoming::EditedCommand(Box::new(command)));
+    }
+    if !is_command && message.text.is_some() {
+        return Some(Incoming::Text(Box::new(message)));
+    }
+    decide_command(message).map(|command| Incoming::Command(Box::new(command)))
+}
+
+#[async_trait]
+pub trait EventHandler: Send + Sync {
+    async fn handle_event(&self, ctx: Arc<Bot>, event: Event);
+
+    /// Time after which the handler is cancelled, instead of the registry's default.
+    fn timeout(&self) -> Option<Duration> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{route_update, Incoming};
+    use crate::types::fixtures::{message, USER_ID};
+    use crate::types::Update;
+
+    fn business_command(from: i64) -> Update {
+        message("/echo hi")
+            .entity("bot_command", "/echo")
+            .business("conn")
+            .from(from)
+            .update("business_message")
+    }
+
+    #[test]
+    fn tells_owner_messages_from_customer_messages() {
+        let Some(Incoming::Command(customer)) = route_update(business_command(USER_ID)) else {
+            panic!("expected a command");
+        };
+        assert!(!customer.message.is_from_business_owner());
+        let Some(Incoming::Command(owner)) = route_update(business_command(7)) else {
+            panic!("expected a command");
+        };
+        assert!(owner.message.is_from_business_owner());
+    }
+
+    #[test]
+    fn drops_messages_sent_by_the_bot() {
+        let update = message("/echo hi")
+            .entity("bot_command", "/echo")
+            .business("conn")
+            .from(7)
+            .sent_by_business_bot()
+            .update("business_message");
+        assert!(route_update(update).is_none());
+    }
+
+    #[test]
+    fn routes_edits_with_non_ascii_text() {
+        let update = message("привет https://x.com")
+            .entity("url", "https://x.com")
+            .update("edited_message");
+        assert!(route_update(update).is_none());
+    }
+}
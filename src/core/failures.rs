@@ -0,0 +1,82 @@
+use crate::core::bot::Bot;
+use crate::types::Message;
+use async_trait::async_trait;
+use futures_util::FutureExt;
+use std::any::Any;
+use std::fmt::Display;
+use std::future::Future;
+use std::panic::AssertUnwindSafe;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Time a handler may run unless configured otherwise.
+pub const DEFAULT_HANDLER_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Why a handler did not finish.
+#[derive(Debug, Clone)]
+pub enum HandlerError {
+    /// The handler panicked with this message
+    Panic(String),
+    /// The handler ran longer than this and was cancelled
+    Timeout(Duration),
+}
+
+impl Display for HandlerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HandlerError::Panic(message) => write!(f, "panicked: {message}"),
+            HandlerError::Timeout(timeout) => write!(f, "timed out after {timeout:?}"),
+        }
+    }
+}
+
+/// A handler that failed, with the update it was handling.
+#[derive(Debug, Clone)]
+pub struct HandlerFailure {
+    /// Command or event name, as in the metrics
+    pub handler: &'static str,
+    /// Short description of the update, for logs
+    pub update: String,
+    /// The message that triggered the handler, if any
+    pub message: Option<Message>,
+    pub error: HandlerError,
+}
+
+impl Display for HandlerFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Handler {} {} handling {}",
+            self.handler, self.error, self.update
+        )
+    }
+}
+
+/// Called after a handler panicked or timed out, e.g. to apologize to the user.
+#[async_trait]
+pub trait ErrorHandler: Send + Sync {
+    async fn failed(&self, ctx: Arc<Bot>, failure: HandlerFailure);
+}
+
+/// Runs a handler future, catching its panics and cancelling it after `timeout`.
+pub async fn guard<F>(future: F, timeout: Duration) -> Result<(), HandlerError>
+where
+    F: Future<Output = ()>,
+{
+    match tokio::time::timeout(timeout, AssertUnwindSafe(future).catch_unwind()).await {
+        Ok(Ok(())) => Ok(()),
+        Ok(Err(payload)) => Err(HandlerError::Panic(panic_message(payload.as_ref()))),
+        Err(_) => Err(HandlerError::Timeout(timeout)),
+    }
+}
+
+/// Text of a panic, if it was raised with a message.
+pub fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        String::from("unknown panic")
+    }
+}
@@ -0,0 +1,45 @@
+use crate::types::Message;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+
+/// Messages whose replies are remembered; the oldest are forgotten first.
+const CAPACITY: usize = 1000;
+
+#[derive(Default)]
+struct Entries {
+    replies: HashMap<(i64, i64), Message>,
+    order: VecDeque<(i64, i64)>,
+}
+
+/// The bot's latest reply to each recent message, so the handler of an
+/// edited command can update its answer instead of posting a new one.
+#[derive(Default)]
+pub struct ReplyLog {
+    entries: Mutex<Entries>,
+}
+
+impl ReplyLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Remembers `reply` as the answer to `message`.
+    pub fn record(&self, message: &Message, reply: &Message) {
+        let key = (message.chat.id(), message.message_id);
+        let mut entries = self.entries.lock().unwrap();
+        if entries.replies.insert(key, reply.clone()).is_none() {
+            entries.order.push_back(key);
+        }
+        while entries.order.len() > CAPACITY {
+            if let Some(oldest) = entries.order.pop_front() {
+                entries.replies.remove(&oldest);
+            }
+        }
+    }
+
+    /// The latest reply to `message`, if it is still remembered.
+    pub fn reply_to(&self, message: &Message) -> Option<Message> {
+        let key = (message.chat.id(), message.message_id);
+        self.entries.lock().unwrap().replies.get(&key).cloned()
+    }
+}
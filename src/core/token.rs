@@ -0,0 +1,79 @@
+use std::fmt::{Debug, Display};
+
+const MASK: &str = "***";
+
+/// Bot API token.
+/// `Debug` and `Display` only show the bot ID part (`123456:***`),
+/// the secret is available through `expose` for building request URLs.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Token(String);
+
+impl Token {
+    pub fn new(token: String) -> Self {
+        Self(token)
+    }
+
+    /// Returns the raw token. Never print the result.
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+
+    /// Returns the bot ID, which is the part of the token before the colon.
+    pub fn bot_id(&self) -> &str {
+        self.0.split(':').next().unwrap_or_default()
+    }
+
+    /// Replaces every occurrence of this token in `text` with its masked form.
+    pub fn redact(&self, text: &str) -> String {
+        if self.0.is_empty() {
+            return redact(text);
+        }
+        redact(&text.replace(&self.0, &self.to_string()))
+    }
+}
+
+impl From<String> for Token {
+    fn from(token: String) -> Self {
+        Self::new(token)
+    }
+}
+
+impl Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{MASK}", self.bot_id())
+    }
+}
+
+impl Debug for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Token({self})")
+    }
+}
+
+/// Masks anything that looks like a token in a Bot API URL (`/bot<id>:<secret>`).
+/// Used where the token itself is not known, e.g. inside the HTTP clients.
+pub fn redact(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(pos) = rest.find("/bot") {
+        let (head, tail) = rest.split_at(pos + "/bot".len());
+        result.push_str(head);
+        let id_len = tail.chars().take_while(|c| c.is_ascii_digit()).count();
+        let secret = &tail[id_len..];
+        if id_len == 0 || !secret.starts_with(':') {
+            rest = tail;
+            continue;
+        }
+        let secret_len = secret[1..]
+            .chars()
+            .take_while(|c| c.is_ascii_alphanumeric() || *c == '_' || *c == '-')
+            .count();
+        result.push_str(&tail[..id_len + 1]);
+        if secret_len > 0 {
+            result.push_str(MASK);
+        }
+        rest = &secret[1 + secret_len..];
+    }
+    result.push_str(rest);
+    result
+}
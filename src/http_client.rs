@@ -1,5 +1,112 @@
+use futures_core::Stream;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::fmt::Debug;
 use std::future::Future;
+use std::pin::Pin;
+use std::time::Duration;
+
+/// A complete HTTP response.
+#[derive(Debug, Clone)]
+pub struct HttpResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl HttpResponse {
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+
+    /// First value of the header, the name is case-insensitive.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        header(&self.headers, name)
+    }
+
+    /// The body as text, invalid UTF-8 replaced.
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.body).into_owned()
+    }
+}
+
+/// Chunks of a response body, in the order they arrive.
+pub type BodyStream<E> = Pin<Box<dyn Stream<Item = Result<Vec<u8>, E>> + Send>>;
+
+/// An HTTP response whose body is read as it arrives, e.g. a large file.
+pub struct HttpStream<E> {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: BodyStream<E>,
+}
+
+impl<E> HttpStream<E> {
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+
+    pub fn header(&self, name: &str) -> Option<&str> {
+        header(&self.headers, name)
+    }
+}
+
+fn header<'a>(headers: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    headers
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case(name))
+        .map(|(_, value)| value.as_str())
+}
+
+/// A proxy all requests go through. Without one, the standard `HTTP_PROXY`,
+/// `HTTPS_PROXY`, `ALL_PROXY` and `NO_PROXY` environment variables apply.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ProxySettings {
+    /// `http://`, `https://`, `socks5://` or `socks5h://` (DNS resolved by the proxy) URL
+    pub url: String,
+    #[serde(default)]
+    pub username: Option<String>,
+    #[serde(default)]
+    pub password: Option<String>,
+    /// Comma-separated hosts reached directly, e.g. a local Bot API server
+    #[serde(default)]
+    pub no_proxy: Option<String>,
+}
+
+impl ProxySettings {
+    pub fn new(url: &str) -> Self {
+        Self {
+            url: url.to_string(),
+            username: None,
+            password: None,
+            no_proxy: None,
+        }
+    }
+
+    pub fn basic_auth(mut self, username: &str, password: &str) -> Self {
+        self.username = Some(username.to_string());
+        self.password = Some(password.to_string());
+        self
+    }
+}
+
+impl Debug for ProxySettings {
+    /// Hides the credentials, also those embedded in the URL.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let url = match self.url.split_once("://") {
+            Some((scheme, rest)) if rest.split('/').next().unwrap_or_default().contains('@') => {
+                let host = rest.split_once('@').map(|(_, host)| host).unwrap_or(rest);
+                format!("{scheme}://***@{host}")
+            }
+            _ => self.url.clone(),
+        };
+        f.debug_struct("ProxySettings")
+            .field("url", &url)
+            .field("username", &self.username)
+            .field("password", &self.password.as_ref().map(|_| "***"))
+            .field("no_proxy", &self.no_proxy)
+            .finish()
+    }
+}
 
 pub trait HttpClient {
     type Error;
@@ -7,17 +114,31 @@ pub trait HttpClient {
     fn get<'a>(
         &'a self,
         url: &'a str,
-    ) -> impl Future<Output = Result<String, Self::Error>> + Send + 'a;
+    ) -> impl Future<Output = Result<HttpResponse, Self::Error>> + Send + 'a;
+    /// Like `get`, but hands out the body in chunks instead of buffering
+    /// it, e.g. to save a large file.
+    fn get_stream<'a>(
+        &'a self,
+        url: &'a str,
+    ) -> impl Future<Output = Result<HttpStream<Self::Error>, Self::Error>> + Send + 'a;
     fn post<'a>(
         &'a self,
         url: &'a str,
         body: String,
-    ) -> impl Future<Output = Result<String, Self::Error>> + Send + 'a;
+    ) -> impl Future<Output = Result<HttpResponse, Self::Error>> + Send + 'a;
+    /// Like `post`, for a request the server may hold for up to `wait`
+    /// before answering, e.g. `getUpdates`.
+    fn long_poll<'a>(
+        &'a self,
+        url: &'a str,
+        body: String,
+        wait: Duration,
+    ) -> impl Future<Output = Result<HttpResponse, Self::Error>> + Send + 'a;
     fn post_multipart<'a>(
         &'a self,
         url: &'a str,
         body: HashMap<String, String>,
         file: Option<&'a str>,
-    ) -> impl Future<Output = Result<String, Self::Error>> + Send + 'a;
+    ) -> impl Future<Output = Result<HttpResponse, Self::Error>> + Send + 'a;
     fn format_error(&self, error: Self::Error) -> String;
 }
@@ -0,0 +1,353 @@
+use crate::core::token::redact;
+use crate::http_client::{HttpClient, HttpResponse, HttpStream};
+use bytes::Bytes;
+use futures_util::{StreamExt, TryStreamExt};
+use http_body_util::combinators::BoxBody;
+use http_body_util::{BodyExt, Full, StreamBody};
+use hyper::body::{Frame, Incoming};
+use hyper::{Method, Request, Response};
+use hyper_tls::HttpsConnector;
+use hyper_util::client::legacy::connect::HttpConnector;
+use hyper_util::client::legacy::Client;
+use hyper_util::rt::{TokioExecutor, TokioTimer};
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::Display;
+use std::future::Future;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+use tokio::io::AsyncReadExt;
+
+/// Time to establish a connection.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+/// Time for a whole request, on top of the wait of a long poll.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+/// Idle connections are closed after this time.
+const POOL_IDLE_TIMEOUT: Duration = Duration::from_secs(90);
+/// Interval of TCP keep-alive probes.
+const TCP_KEEPALIVE: Duration = Duration::from_secs(60);
+/// Size of the chunks an upload is read from disk in.
+const UPLOAD_CHUNK: usize = 64 * 1024;
+
+/// Body of a request: small bodies are sent from memory, files are streamed.
+type RequestBody = BoxBody<Bytes, std::io::Error>;
+
+/// Why a request made with `HyperHttpClient` failed.
+#[derive(Debug)]
+pub enum HyperError {
+    /// The URL or a header is invalid
+    InvalidRequest(hyper::http::Error),
+    /// Connecting or sending the request failed
+    Request(hyper_util::client::legacy::Error),
+    /// Reading the response body failed
+    Body(hyper::Error),
+    /// Reading the file to upload failed
+    Io(std::io::Error),
+    Timeout,
+}
+
+impl Display for HyperError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let err: &dyn Error = match self {
+            HyperError::InvalidRequest(err) => err,
+            HyperError::Request(err) => err,
+            HyperError::Body(err) => err,
+            HyperError::Io(err) => err,
+            HyperError::Timeout => return write!(f, "request timed out"),
+        };
+        write!(f, "{err}")?;
+        // The client error says little by itself, e.g. "client error (Connect)".
+        let mut source = err.source();
+        while let Some(err) = source {
+            write!(f, ": {err}")?;
+            source = err.source();
+        }
+        Ok(())
+    }
+}
+
+/// A lean HTTP client on top of hyper with a connection pool and
+/// native TLS. Proxies are not supported.
+#[derive(Clone)]
+pub struct HyperHttpClient {
+    client: Client<HttpsConnector<HttpConnector>, RequestBody>,
+    request_timeout: Duration,
+}
+
+impl HyperHttpClient {
+    pub fn new() -> Self {
+        Self::with_timeouts(CONNECT_TIMEOUT, REQUEST_TIMEOUT)
+    }
+
+    /// `request_timeout` applies to whole requests; long polls get their
+    /// wait added on top.
+    pub fn with_timeouts(connect_timeout: Duration, request_timeout: Duration) -> Self {
+        let mut http = HttpConnector::new();
+        http.enforce_http(false);
+        http.set_connect_timeout(Some(connect_timeout));
+        http.set_keepalive(Some(TCP_KEEPALIVE));
+        let client = Client::builder(TokioExecutor::new())
+            .pool_timer(TokioTimer::new())
+            .pool_idle_timeout(POOL_IDLE_TIMEOUT)
+            .build(HttpsConnector::new_with_connector(http));
+        Self {
+            client,
+            request_timeout,
+        }
+    }
+
+    async fn send(
+        &self,
+        request: Request<RequestBody>,
+        timeout: Option<Duration>,
+    ) -> Result<HttpResponse, HyperError> {
+        println!(
+            "Request: {} {}",
+            request.method(),
+            redact(&request.uri().to_string())
+        );
+        let response = async {
+            let response = self
+                .client
+                .request(request)
+                .await
+                .map_err(HyperError::Request)?;
+            read_response(response).await
+        };
+        let response = with_timeout(timeout, response).await?;
+        println!("{}", response.text());
+        Ok(response)
+    }
+
+    fn json_request(url: &str, body: String) -> Result<Request<RequestBody>, HyperError> {
+        Request::builder()
+            .method(Method::POST)
+            .uri(url)
+            .header("Content-Type", "application/json")
+            .body(full(body))
+            .map_err(HyperError::InvalidRequest)
+    }
+}
+
+impl Default for HyperHttpClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+async fn with_timeout<F, R>(timeout: Option<Duration>, future: F) -> Result<R, HyperError>
+where
+    F: Future<Output = Result<R, HyperError>>,
+{
+    match timeout {
+        Some(timeout) => tokio::time::timeout(timeout, future)
+            .await
+            .map_err(|_| HyperError::Timeout)?,
+        None => future.await,
+    }
+}
+
+fn headers_of(response: &Response<Incoming>) -> Vec<(String, String)> {
+    response
+        .headers()
+        .iter()
+        .map(|(name, value)| {
+            (
+                name.to_string(),
+                String::from_utf8_lossy(value.as_bytes()).into_owned(),
+            )
+        })
+        .collect()
+}
+
+async fn read_response(response: Response<Incoming>) -> Result<HttpResponse, HyperError> {
+    let status = response.status().as_u16();
+    let headers = headers_of(&response);
+    let body = response
+        .into_body()
+        .collect()
+        .await
+        .map_err(HyperError::Body)?
+        .to_bytes()
+        .to_vec();
+    Ok(HttpResponse {
+        status,
+        headers,
+        body,
+    })
+}
+
+/// A body sent from memory.
+fn full(body: impl Into<Bytes>) -> RequestBody {
+    Full::new(body.into())
+        .map_err(|never| match never {})
+        .boxed()
+}
+
+/// A `multipart/form-data` body with the file streamed from disk, so large
+/// uploads don't have to fit in memory.
+struct MultipartBody {
+    boundary: String,
+    length: u64,
+    body: RequestBody,
+}
+
+async fn multipart_body(
+    fields: HashMap<String, String>,
+    file: Option<&str>,
+) -> Result<MultipartBody, HyperError> {
+    let nanos = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|now| now.as_nanos())
+        .unwrap_or_default();
+    let boundary = format!("rust-telegram-bot-{nanos:x}");
+    let mut head = Vec::new();
+    for (name, value) in fields {
+        head.extend_from_slice(
+            format!("--{boundary}\r\nContent-Disposition: form-data; name=\"{name}\"\r\nContent-Type: text/plain\r\n\r\n{value}\r\n")
+                .as_bytes(),
+        );
+    }
+    let Some(file) = file else {
+        head.extend_from_slice(format!("--{boundary}--\r\n").as_bytes());
+        return Ok(MultipartBody {
+            boundary,
+            length: head.len() as u64,
+            body: full(head),
+        });
+    };
+    let file_name = PathBuf::from(file)
+        .file_name()
+        .and_then(|x| x.to_str())
+        .unwrap_or(file)
+        .to_string();
+    head.extend_from_slice(
+        format!("--{boundary}\r\nContent-Disposition: form-data; name=\"{file_name}\"; filename=\"{file_name}\"\r\nContent-Type: application/octet-stream\r\n\r\n")
+            .as_bytes(),
+    );
+    let tail = format!("\r\n--{boundary}--\r\n").into_bytes();
+    let mut content = tokio::fs::File::open(file).await.map_err(HyperError::Io)?;
+    let size = content.metadata().await.map_err(HyperError::Io)?.len();
+    let length = head.len() as u64 + size + tail.len() as u64;
+    let chunks = async_stream::try_stream! {
+        yield Bytes::from(head);
+        let mut buf = vec![0u8; UPLOAD_CHUNK];
+        loop {
+            let read = content.read(&mut buf).await?;
+            if read == 0 {
+                break;
+            }
+            yield Bytes::copy_from_slice(&buf[..read]);
+        }
+        yield Bytes::from(tail);
+    };
+    Ok(MultipartBody {
+        boundary,
+        length,
+        body: BodyExt::boxed(StreamBody::new(chunks.map_ok(Frame::data))),
+    })
+}
+
+impl HttpClient for HyperHttpClient {
+    type Error = HyperError;
+
+    async fn get(&self, url: &str) -> Result<HttpResponse, Self::Error> {
+        let request = Request::builder()
+            .uri(url)
+            .body(full(Bytes::new()))
+            .map_err(HyperError::InvalidRequest)?;
+        self.send(request, Some(self.request_timeout)).await
+    }
+
+    async fn get_stream(&self, url: &str) -> Result<HttpStream<Self::Error>, Self::Error> {
+        let request = Request::builder()
+            .uri(url)
+            .body(full(Bytes::new()))
+            .map_err(HyperError::InvalidRequest)?;
+        // Downloads can take long, only the connect timeout applies.
+        let response = self
+            .client
+            .request(request)
+            .await
+            .map_err(HyperError::Request)?;
+        Ok(HttpStream {
+            status: response.status().as_u16(),
+            headers: headers_of(&response),
+            body: Box::pin(
+                response
+                    .into_body()
+                    .into_data_stream()
+                    .map(|chunk| chunk.map(|chunk| chunk.to_vec()).map_err(HyperError::Body)),
+            ),
+        })
+    }
+
+    async fn post(&self, url: &str, body: String) -> Result<HttpResponse, Self::Error> {
+        let request = Self::json_request(url, body)?;
+        self.send(request, Some(self.request_timeout)).await
+    }
+
+    async fn long_poll(
+        &self,
+        url: &str,
+        body: String,
+        wait: Duration,
+    ) -> Result<HttpResponse, Self::Error> {
+        let request = Self::json_request(url, body)?;
+        self.send(request, Some(self.request_timeout + wait)).await
+    }
+
+    async fn post_multipart(
+        &self,
+        url: &str,
+        body: HashMap<String, String>,
+        file: Option<&str>,
+    ) -> Result<HttpResponse, Self::Error> {
+        let multipart = multipart_body(body, file).await?;
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri(url)
+            .header(
+                "Content-Type",
+                format!("multipart/form-data; boundary={}", multipart.boundary),
+            )
+            .header("Content-Length", multipart.length)
+            .body(multipart.body)
+            .map_err(HyperError::InvalidRequest)?;
+        // Uploads can take long, only the connect timeout applies.
+        self.send(request, None).await
+    }
+
+    fn format_error(&self, err: HyperError) -> String {
+        redact(&err.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{multipart_body, UPLOAD_CHUNK};
+    use http_body_util::BodyExt;
+    use std::collections::HashMap;
+
+    #[tokio::test]
+    async fn streams_the_file_with_the_announced_length() {
+        let path = std::env::temp_dir().join(format!("upload-{}.bin", std::process::id()));
+        let content: Vec<u8> = (0..UPLOAD_CHUNK * 2 + 7).map(|i| i as u8).collect();
+        tokio::fs::write(&path, &content).await.unwrap();
+        let fields = HashMap::from([("chat_id".to_string(), "42".to_string())]);
+        let multipart = multipart_body(fields, path.to_str()).await.unwrap();
+        let body = multipart.body.collect().await.unwrap().to_bytes();
+        tokio::fs::remove_file(&path).await.unwrap();
+
+        assert_eq!(body.len() as u64, multipart.length);
+        assert!(body.windows(content.len()).any(|w| w == content));
+        let tail = format!("\r\n--{}--\r\n", multipart.boundary);
+        assert!(body.ends_with(tail.as_bytes()));
+    }
+
+    #[tokio::test]
+    async fn fails_for_a_missing_file() {
+        let missing = multipart_body(HashMap::new(), Some("/nonexistent/upload.bin")).await;
+        assert!(missing.is_err());
+    }
+}
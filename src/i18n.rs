@@ -0,0 +1,150 @@
+pub use crate::i18n::catalog::{Catalog, Translations, DEFAULT_LOCALES_DIR};
+pub use crate::i18n::plural::plural_category;
+use crate::storage::{Namespace, Storage};
+use crate::types::{Message, User};
+use std::sync::Arc;
+
+mod catalog;
+mod plural;
+
+/// Storage key of the per-user and per-chat locale overrides.
+const LOCALE_KEY: &str = "i18n.locale";
+
+/// Translates `key` with a `Translator`, optionally substituting named arguments:
+///
+/// ```ignore
+/// let tr = ctx.i18n.for_message(&command.message).await;
+/// let text = t!(tr, "schedule.scheduled", id = job_id);
+/// ```
+#[macro_export]
+macro_rules! t {
+    ($tr:expr, $key:expr) => {
+        $tr.translate($key, &[])
+    };
+    ($tr:expr, $key:expr, $($name:ident = $value:expr),+ $(,)?) => {
+        $tr.translate($key, &[$((stringify!($name), $value.to_string())),+])
+    };
+}
+
+/// Two-letter ISO 639-1 language of a locale, e.g. `pt` for `pt-br`, as
+/// the Bot API expects in `language_code`. `None` if the locale has none.
+pub fn language_code(locale: &str) -> Option<&str> {
+    let language = locale.split(['-', '_']).next()?;
+    (language.len() == 2 && language.bytes().all(|b| b.is_ascii_lowercase())).then_some(language)
+}
+
+/// Resolves the locale of incoming messages and hands out translators.
+pub struct I18n {
+    translations: Arc<Translations>,
+    storage: Arc<dyn Storage>,
+}
+
+/// Translations bound to one locale.
+pub struct Translator {
+    translations: Arc<Translations>,
+    locale: String,
+}
+
+impl Translator {
+    pub fn locale(&self) -> &str {
+        &self.locale
+    }
+
+    pub fn translate(&self, key: &str, args: &[(&str, String)]) -> String {
+        self.translations.translate(&self.locale, key, args)
+    }
+}
+
+impl I18n {
+    pub fn new(translations: Translations, storage: Arc<dyn Storage>) -> Arc<Self> {
+        Arc::new(Self {
+            translations: Arc::new(translations),
+            storage,
+        })
+    }
+
+    /// Shares the translations, but keeps locale overrides in another storage.
+    pub fn with_storage(&self, storage: Arc<dyn Storage>) -> Arc<Self> {
+        Arc::new(Self {
+            translations: self.translations.clone(),
+            storage,
+        })
+    }
+
+    pub fn translations(&self) -> &Translations {
+        &self.translations
+    }
+
+    pub fn translator(&self, locale: &str) -> Translator {
+        Translator {
+            translations: self.translations.clone(),
+            locale: locale.to_string(),
+        }
+    }
+
+    /// Picks the locale for a message: the sender's override, then the chat's
+    /// override, then the sender's Telegram language, then the default locale.
+    pub async fn locale_for(&self, message: &Message) -> String {
+        let user = message.from.0.as_ref();
+        let mut candidates = Vec::new();
+        if let Some(user) = user {
+            candidates.push(self.override_for(&Namespace::User(user.id)).await);
+        }
+        candidates.push(self.override_for(&Namespace::Chat(message.chat.id())).await);
+        candidates.push(user.and_then(|user| user.language_code.clone()));
+        self.first_supported(candidates)
+    }
+
+    pub async fn for_message(&self, message: &Message) -> Translator {
+        self.translator(&self.locale_for(message).await)
+    }
+
+    /// Picks the locale for an update without a chat, e.g. a pre-checkout
+    /// query: the user's override, then their Telegram language.
+    pub async fn for_user(&self, user: &User) -> Translator {
+        let candidates = vec![
+            self.override_for(&Namespace::User(user.id)).await,
+            user.language_code.clone(),
+        ];
+        self.translator(&self.first_supported(candidates))
+    }
+
+    /// The first language tag with translations, or the default locale.
+    fn first_supported(&self, candidates: Vec<Option<String>>) -> String {
+        candidates
+            .into_iter()
+            .flatten()
+            .find_map(|tag| self.translations.negotiate(&tag))
+            .unwrap_or_else(|| self.translations.default_locale().to_string())
+    }
+
+    async fn override_for(&self, ns: &Namespace) -> Option<String> {
+        self.storage.get::<String>(ns, LOCALE_KEY).await
+    }
+
+    /// Stores a locale override for a user or chat. Returns the matched
+    /// locale, or `None` if there is no catalog for the language.
+    pub async fn set_override(&self, ns: &Namespace, language_tag: &str) -> Option<String> {
+        let locale = self.translations.negotiate(language_tag)?;
+        self.storage.set(ns, LOCALE_KEY, &locale).await;
+        Some(locale)
+    }
+
+    pub async fn clear_override(&self, ns: &Namespace) -> bool {
+        self.storage.delete(ns, LOCALE_KEY).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::language_code;
+
+    #[test]
+    fn strips_the_region_from_locales() {
+        assert_eq!(language_code("en"), Some("en"));
+        assert_eq!(language_code("pt-br"), Some("pt"));
+        assert_eq!(language_code("zh_hant"), Some("zh"));
+        assert_eq!(language_code("fil"), None);
+        assert_eq!(language_code(""), None);
+    }
+}
@@ -0,0 +1,154 @@
+use crate::i18n::plural::plural_category;
+use std::collections::HashMap;
+
+/// Translations of one locale, loaded from a `<locale>.txt` file:
+///
+/// ```text
+/// # Comment
+/// greeting = Hello, {name}!
+/// jobs.count[one] = {count} job
+/// jobs.count[other] = {count} jobs
+/// ```
+///
+/// Keys with `[category]` suffixes are plural forms, picked by the `count` argument.
+#[derive(Debug, Default)]
+pub struct Catalog {
+    messages: HashMap<String, String>,
+}
+
+impl Catalog {
+    pub fn parse(content: &str) -> Self {
+        let messages = content
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .filter_map(|line| line.split_once('='))
+            .map(|(key, value)| (key.trim().to_string(), value.trim().replace("\\n", "\n")))
+            .collect();
+        Self { messages }
+    }
+
+    pub fn contains(&self, key: &str) -> bool {
+        self.messages.contains_key(key) || self.messages.contains_key(&format!("{key}[other]"))
+    }
+
+    /// Looks up the message, choosing the plural form for `count` if there is one.
+    pub fn lookup(&self, language: &str, key: &str, count: Option<i64>) -> Option<&String> {
+        if let Some(count) = count {
+            let category = plural_category(language, count);
+            let form = self
+                .messages
+                .get(&format!("{key}[{category}]"))
+                .or_else(|| self.messages.get(&format!("{key}[other]")));
+            if form.is_some() {
+                return form;
+            }
+        }
+        self.messages.get(key)
+    }
+}
+
+/// Catalogs of all locales, with a fallback locale for missing keys.
+#[derive(Debug)]
+pub struct Translations {
+    catalogs: HashMap<String, Catalog>,
+    default_locale: String,
+}
+
+impl Translations {
+    pub fn new(default_locale: &str) -> Self {
+        Self {
+            catalogs: HashMap::new(),
+            default_locale: default_locale.to_string(),
+        }
+    }
+
+    /// Loads every `<locale>.txt` file in the directory.
+    pub fn load_dir(dir: &str, default_locale: &str) -> Self {
+        let mut translations = Self::new(default_locale);
+        let entries = match std::fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(err) => {
+                println!("Error reading locales from {dir}: {err}");
+                return translations;
+            }
+        };
+        for path in entries.flatten().map(|entry| entry.path()) {
+            if path.extension().and_then(|ext| ext.to_str()) != Some("txt") {
+                continue;
+            }
+            let Some(locale) = path.file_stem().and_then(|stem| stem.to_str()) else {
+                continue;
+            };
+            match std::fs::read_to_string(&path) {
+                Ok(content) => translations.add(locale, Catalog::parse(&content)),
+                Err(err) => println!("Error reading {}: {err}", path.display()),
+            }
+        }
+        println!("Loaded locales: {:?}", translations.locales());
+        translations
+    }
+
+    pub fn add(&mut self, locale: &str, catalog: Catalog) {
+        self.catalogs.insert(locale.to_lowercase(), catalog);
+    }
+
+    pub fn default_locale(&self) -> &str {
+        &self.default_locale
+    }
+
+    pub fn locales(&self) -> Vec<&str> {
+        let mut locales: Vec<&str> = self.catalogs.keys().map(String::as_str).collect();
+        locales.sort();
+        locales
+    }
+
+    pub fn catalog(&self, locale: &str) -> Option<&Catalog> {
+        self.catalogs.get(locale)
+    }
+
+    /// Finds the best available locale for a language tag:
+    /// `pt-BR` matches `pt-br` first, then `pt`.
+    pub fn negotiate(&self, language_tag: &str) -> Option<String> {
+        let tag = language_tag.to_lowercase().replace('_', "-");
+        if self.catalogs.contains_key(&tag) {
+            return Some(tag);
+        }
+        let language = tag.split('-').next()?;
+        self.catalogs
+            .contains_key(language)
+            .then(|| language.to_string())
+    }
+
+    /// Translates `key` into the locale, substituting `{name}` placeholders.
+    /// Falls back to the default locale and then to the key itself.
+    pub fn translate(&self, locale: &str, key: &str, args: &[(&str, String)]) -> String {
+        let count = args
+            .iter()
+            .find(|(name, _)| *name == "count")
+            .and_then(|(_, value)| value.parse::<i64>().ok());
+        let message = [locale, self.default_locale.as_str()]
+            .iter()
+            .find_map(|locale| {
+                let language = locale.split('-').next().unwrap_or(locale);
+                self.catalogs.get(*locale)?.lookup(language, key, count)
+            });
+        let Some(message) = message else {
+            return key.to_string();
+        };
+        let mut text = message.clone();
+        for (name, value) in args {
+            text = text.replace(&format!("{{{name}}}"), value);
+        }
+        text
+    }
+}
+
+impl Default for Translations {
+    fn default() -> Self {
+        Self::new("en")
+    }
+}
+
+/// Directory the catalogs are read from when `LOCALES_DIR` is not set.
+pub const DEFAULT_LOCALES_DIR: &str = "locales";
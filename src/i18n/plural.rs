@@ -0,0 +1,58 @@
+/// Plural category of `n` in the given language, as named by CLDR:
+/// zero, one, two, few, many or other.
+/// Covers the languages with the most Telegram users, everything else
+/// falls back to the English one/other rule.
+pub fn plural_category(language: &str, n: i64) -> &'static str {
+    let n = n.unsigned_abs();
+    let (last, last_two) = (n % 10, n % 100);
+    match language {
+        // No plural forms
+        "ja" | "ko" | "zh" | "vi" | "th" | "id" | "ms" | "tr" | "fa" => "other",
+        // 0 and 1 are singular
+        "fr" | "pt" | "hi" => {
+            if n <= 1 {
+                "one"
+            } else {
+                "other"
+            }
+        }
+        "ru" | "uk" | "be" => {
+            if last == 1 && last_two != 11 {
+                "one"
+            } else if (2..=4).contains(&last) && !(12..=14).contains(&last_two) {
+                "few"
+            } else {
+                "many"
+            }
+        }
+        "pl" => {
+            if n == 1 {
+                "one"
+            } else if (2..=4).contains(&last) && !(12..=14).contains(&last_two) {
+                "few"
+            } else {
+                "many"
+            }
+        }
+        "cs" | "sk" => match n {
+            1 => "one",
+            2..=4 => "few",
+            _ => "other",
+        },
+        "ar" => match (n, last_two) {
+            (0, _) => "zero",
+            (1, _) => "one",
+            (2, _) => "two",
+            (_, 3..=10) => "few",
+            (_, 11..=99) => "many",
+            _ => "other",
+        },
+        _ => {
+            if n == 1 {
+                "one"
+            } else {
+                "other"
+            }
+        }
+    }
+}
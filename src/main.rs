@@ -1,13 +1,30 @@
 use crate::api_client::ApiClient;
-use crate::commands::EchoCommandHandler;
-use crate::core::{bot, decide_command, Command, CommandRegistry, Registry};
+use crate::commands::{
+    locale_button, AccessDeniedHandler, AclCommandHandler, DonateCommandHandler,
+    EchoCommandHandler, ErrorReplyHandler, LanguageCommandHandler, PollCommandHandler,
+    ReminderJobHandler, ScheduleCommandHandler, REMINDER_JOB,
+};
+use crate::core::{AclConfig, BotCommand, CommandRegistry, EventKind};
 use crate::http_client::HttpClient;
+#[cfg(any(feature = "reqwest-client", feature = "hyper-client"))]
+use crate::http_client::ProxySettings;
+#[cfg(all(feature = "hyper-client", not(feature = "reqwest-client")))]
+use crate::http_clients::HyperHttpClient;
+#[cfg(feature = "reqwest-client")]
 use crate::http_clients::ReqwestHttpClient;
-use crate::types::Bot;
+use crate::i18n::{language_code, I18n, Translations, DEFAULT_LOCALES_DIR};
+use crate::metrics::Metrics;
+use crate::payments::PaymentFlow;
+use crate::polls::PollTracker;
+use crate::scheduler::Scheduler;
+use crate::supervisor::{
+    BotInstance, BotSettings, PollingHealth, Services, SourceHealth, Supervisor, SupervisorConfig,
+};
+use crate::types::CommandDescription;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
-use tokio::sync::mpsc;
+use std::time::Duration;
 
-use futures_util::StreamExt;
 use std::env;
 
 pub mod api_client;
@@ -15,104 +32,313 @@ pub mod commands;
 pub mod core;
 pub mod http_client;
 pub mod http_clients;
+pub mod i18n;
+pub mod metrics;
+pub mod payments;
+pub mod polls;
+pub mod scheduler;
+pub mod storage;
+pub mod supervisor;
 pub mod types;
 
-fn print_me(resp: Option<Bot>) {
-    if let Some(resp) = resp {
-        println!("Status: OK");
+#[cfg(not(any(feature = "reqwest-client", feature = "hyper-client")))]
+compile_error!("enable the reqwest-client or hyper-client feature");
+
+/// Creates an HTTP client going through the proxy, or through the one
+/// from the environment.
+#[cfg(feature = "reqwest-client")]
+fn http_client(proxy: Option<ProxySettings>) -> Option<Arc<ReqwestHttpClient>> {
+    if let Some(proxy) = &proxy {
+        println!("Using proxy: {proxy:?}");
+    }
+    ReqwestHttpClient::builder()
+        .proxy(proxy)
+        .build()
+        .map(Arc::new)
+        .map_err(|err| println!("Error creating HTTP client: {err}"))
+        .ok()
+}
+
+/// Creates a hyper HTTP client, which connects directly. Fails if a proxy
+/// is configured, since bypassing it may break the network policy.
+#[cfg(all(feature = "hyper-client", not(feature = "reqwest-client")))]
+fn http_client(proxy: Option<ProxySettings>) -> Option<Arc<HyperHttpClient>> {
+    if let Some(proxy) = proxy {
         println!(
-            "My ID: {}\nMy name is: {} ({})",
-            resp.id, resp.first_name, resp.username
+            "Error: the hyper HTTP client doesn't support proxies, remove {proxy:?} or enable the reqwest-client feature"
         );
-        if resp.is_bot {
-            println!("I am a bot!");
-        } else {
-            println!("I am not a bot!");
-        }
-    } else {
-        println!("Status: ERROR");
+        return None;
     }
+    Some(Arc::new(HyperHttpClient::new()))
 }
 
-async fn updates_loop<T>(api_client: Arc<ApiClient<T>>, tx: mpsc::Sender<Command>)
+/// Publishes the command menu in every locale that has command descriptions.
+async fn push_command_menu<T>(api_client: &ApiClient<T>, i18n: &I18n)
 where
     T: HttpClient,
 {
-    print_me(api_client.get_me().await);
+    let translations = i18n.translations();
+    let mut languages = HashSet::new();
+    for locale in translations.locales() {
+        let Some(catalog) = translations.catalog(locale) else {
+            continue;
+        };
+        let tr = i18n.translator(locale);
+        let commands: Vec<CommandDescription> = BotCommand::ALL
+            .iter()
+            .map(|command| (command.name(), format!("command.{}", command.name())))
+            .filter(|(_, key)| catalog.contains(key))
+            .map(|(name, key)| CommandDescription {
+                command: name.to_string(),
+                description: tr.translate(&key, &[]),
+            })
+            .collect();
+        if commands.is_empty() {
+            continue;
+        }
+        if locale == translations.default_locale() {
+            api_client.set_my_commands(&commands, None).await;
+        }
+        // Telegram takes only the language; locales are sorted, so the menu
+        // of `pt` wins over `pt-br`.
+        let Some(language) = language_code(locale) else {
+            println!("Locale {locale} has no two-letter language, skipping its command menu");
+            continue;
+        };
+        if !languages.insert(language) {
+            println!("Command menu of locale {locale} is covered by {language}");
+            continue;
+        }
+        api_client.set_my_commands(&commands, Some(language)).await;
+    }
+}
+
+/// Registers the handlers of one bot and starts its scheduler.
+async fn setup_bot<T>(instance: &BotInstance<T>)
+where
+    T: HttpClient + Send + Sync + 'static,
+{
+    let api_client = &instance.api_client;
+    let registry = &instance.registry;
+    let storage = instance.bot.storage.clone();
+    let command_handler = Arc::new(EchoCommandHandler::new(api_client.clone()));
+    registry.register(BotCommand::Echo, command_handler).await;
+    let acl_handler = Arc::new(AclCommandHandler::new(
+        api_client.clone(),
+        instance.acl.clone(),
+    ));
+    registry
+        .register(BotCommand::Grant, acl_handler.clone())
+        .await;
+    registry.register(BotCommand::Revoke, acl_handler).await;
+    registry
+        .set_denial_handler(Arc::new(AccessDeniedHandler::new(api_client.clone())))
+        .await;
+    registry
+        .set_error_handler(Arc::new(ErrorReplyHandler::new(api_client.clone())))
+        .await;
+    let language_handler = Arc::new(LanguageCommandHandler::new(api_client.clone()));
+    registry
+        .register(BotCommand::Language, language_handler.clone())
+        .await;
+    registry
+        .register(BotCommand::ChatLanguage, language_handler)
+        .await;
+    for locale in instance.bot.i18n.translations().locales() {
+        registry
+            .register_button(&locale_button(locale), BotCommand::Language, &[locale])
+            .await;
+    }
+    let poll_tracker = PollTracker::new(storage.clone());
+    registry
+        .register_event(EventKind::Poll, poll_tracker.clone())
+        .await;
+    registry
+        .register_event(EventKind::PollAnswer, poll_tracker.clone())
+        .await;
+    let poll_handler = Arc::new(PollCommandHandler::new(api_client.clone(), poll_tracker));
+    registry
+        .register(BotCommand::Poll, poll_handler.clone())
+        .await;
+    registry.register(BotCommand::Results, poll_handler).await;
+    let donate_handler = Arc::new(DonateCommandHandler::new(api_client.clone()));
+    registry
+        .register(BotCommand::Donate, donate_handler.clone())
+        .await;
+    let payment_flow = Arc::new(PaymentFlow::new(api_client.clone(), donate_handler));
+    for kind in [
+        EventKind::ShippingQuery,
+        EventKind::PreCheckoutQuery,
+        EventKind::SuccessfulPayment,
+        EventKind::RefundedPayment,
+    ] {
+        registry.register_event(kind, payment_flow.clone()).await;
+    }
+
+    let scheduler = Scheduler::new(storage);
+    scheduler.load().await;
+    scheduler
+        .register_handler(
+            REMINDER_JOB,
+            Arc::new(ReminderJobHandler::new(api_client.clone())),
+        )
+        .await;
+    let schedule_handler = Arc::new(ScheduleCommandHandler::new(
+        api_client.clone(),
+        scheduler.clone(),
+    ));
+    for command in [
+        BotCommand::Remind,
+        BotCommand::Every,
+        BotCommand::Jobs,
+        BotCommand::CancelJob,
+    ] {
+        registry.register(command, schedule_handler.clone()).await;
+    }
+    tokio::spawn(scheduler.run());
+
+    push_command_menu(api_client, &instance.bot.i18n).await;
+}
+
+/// Logs changes of the polling sources and exports their state as metrics.
+async fn watch_health(health: HashMap<String, Arc<SourceHealth>>, metrics: Option<Arc<Metrics>>) {
+    const HEALTH_INTERVAL: Duration = Duration::from_secs(10);
+    let mut last = HashMap::new();
+    let mut interval = tokio::time::interval(HEALTH_INTERVAL);
     loop {
-        println!("Running the generator");
-        let stream = api_client.yield_updates().await;
-        tokio::pin!(stream);
-        println!("Querring the generator");
-        while let Some(update) = stream.next().await {
-            api_client.update_offset(update.update_id);
-            println!(
-                "Update ID: {}\n{}",
-                update.get_update_id(),
-                match (update.edited_message.as_ref(), update.message.as_ref()) {
-                    (Some(edited_message), _) => format!("Edited message: {}", edited_message),
-                    (_, Some(message)) => format!("New message: {}", message),
-                    _ => "No message".to_string(),
-                }
-            );
-            let message = match (update.message, update.edited_message) {
-                (Some(msg), None) => msg,
-                (None, Some(edited_msg)) => edited_msg,
-                _ => {
-                    println!("No message");
-                    continue;
-                }
-            };
-            let cmd = decide_command(message);
-            match cmd {
-                Some(command) => {
-                    tx.send(command).await.unwrap();
-                }
-                None => {
-                    println!("No command");
-                }
-            };
+        interval.tick().await;
+        for (name, source) in &health {
+            let state = source.get().await;
+            if let Some(metrics) = &metrics {
+                metrics.set_polling_state(name, state.state());
+            }
+            if last.get(name).map(PollingHealth::state) != Some(state.state()) {
+                println!("Bot {name}: polling {state}");
+                last.insert(name.clone(), state);
+            }
         }
     }
 }
 
 #[tokio::main]
 async fn main() {
-    let (tx, mut rx) = tokio::sync::mpsc::channel::<Command>(100);
-    let registry = Registry::new();
     const TOKEN_KEY: &str = "TOKEN";
-    let bot_config = Arc::new(match env::var(TOKEN_KEY) {
-        Ok(val) => bot::Bot {
-            token: val.to_owned(),
-            offset: 0.into(),
-            polling_timeout: 30,
-            base_url: String::from("https://api.telegram.org"),
-            handlers: registry.clone(),
+    const BOTS_CONFIG_KEY: &str = "BOTS_CONFIG";
+    // URL of a self-hosted Bot API server running with `--local`.
+    const LOCAL_BOT_API_KEY: &str = "LOCAL_BOT_API";
+    // Comma-separated update types, overriding those derived from the handlers.
+    const ALLOWED_UPDATES_KEY: &str = "ALLOWED_UPDATES";
+    // Most updates fetched by one getUpdates call.
+    const UPDATES_LIMIT_KEY: &str = "UPDATES_LIMIT";
+    // Seconds a handler may run before it is cancelled, overriding the config file.
+    const HANDLER_TIMEOUT_KEY: &str = "HANDLER_TIMEOUT";
+    // Several bots from a config file, or a single one from the token.
+    let (mut config, scope_storage) = match (env::var(BOTS_CONFIG_KEY), env::var(TOKEN_KEY)) {
+        (Ok(path), _) => match SupervisorConfig::from_file(&path) {
+            Some(config) => (config, true),
+            None => return,
         },
-        Err(_) => {
-            println!("Warning: {} is not set. Using default token.", TOKEN_KEY);
+        (Err(_), Ok(token)) => {
+            let mut settings = BotSettings::new("default", token);
+            if let Ok(base_url) = env::var(LOCAL_BOT_API_KEY) {
+                settings = settings.local_server(base_url);
+            }
+            if let Ok(allowed_updates) = env::var(ALLOWED_UPDATES_KEY) {
+                settings.allowed_updates = Some(
+                    allowed_updates
+                        .split(',')
+                        .map(|kind| kind.trim().to_string())
+                        .filter(|kind| !kind.is_empty())
+                        .collect(),
+                );
+            }
+            settings.limit = env::var(UPDATES_LIMIT_KEY)
+                .ok()
+                .and_then(|limit| limit.parse().ok());
+            (SupervisorConfig::single(settings), false)
+        }
+        (Err(_), Err(_)) => {
+            println!(
+                "Warning: neither {} nor {} is set.",
+                BOTS_CONFIG_KEY, TOKEN_KEY
+            );
             return;
         }
-    });
-    let api_client = Arc::new(ApiClient::new(
-        Arc::new(ReqwestHttpClient::new()),
-        bot_config.clone(),
-    ));
-    let command_handler = Arc::new(EchoCommandHandler::new(api_client.clone()));
-    registry
-        .register(crate::core::BotCommand::Echo, command_handler)
-        .await;
-
-    tokio::spawn(updates_loop(api_client.clone(), tx.clone()));
-    tokio::spawn(async move {
-        while let Some(command) = rx.recv().await {
-            bot_config
-                .clone()
-                .handlers
-                .dispatch(bot_config.clone(), command)
-                .await;
+    };
+    if let Some(timeout) = env::var(HANDLER_TIMEOUT_KEY)
+        .ok()
+        .and_then(|timeout| timeout.parse().ok())
+    {
+        config.handler_timeout = timeout;
+    }
+    const ACL_CONFIG_KEY: &str = "ACL_CONFIG";
+    let acl_config = match env::var(ACL_CONFIG_KEY) {
+        Ok(path) => AclConfig::from_file(&path).unwrap_or_default(),
+        Err(_) => AclConfig::default(),
+    };
+    const STORAGE_KEY: &str = "STORAGE";
+    let storage_spec = env::var(STORAGE_KEY).unwrap_or_else(|_| String::from("memory"));
+    let Some(storage) = storage::open(&storage_spec) else {
+        println!(
+            "Error: cannot open storage {}={}",
+            STORAGE_KEY, storage_spec
+        );
+        return;
+    };
+    const LOCALES_DIR_KEY: &str = "LOCALES_DIR";
+    const DEFAULT_LOCALE_KEY: &str = "DEFAULT_LOCALE";
+    let translations = Translations::load_dir(
+        &env::var(LOCALES_DIR_KEY).unwrap_or_else(|_| String::from(DEFAULT_LOCALES_DIR)),
+        &env::var(DEFAULT_LOCALE_KEY).unwrap_or_else(|_| String::from("en")),
+    );
+    let i18n = I18n::new(translations, storage.clone());
+    const METRICS_ADDR_KEY: &str = "METRICS_ADDR";
+    let metrics = match env::var(METRICS_ADDR_KEY).map(|addr| addr.parse()) {
+        Ok(Ok(addr)) => {
+            let metrics = Metrics::new();
+            tokio::spawn(metrics.clone().serve(addr));
+            Some(metrics)
+        }
+        Ok(Err(err)) => {
+            println!(
+                "Warning: {} is not a valid address: {}",
+                METRICS_ADDR_KEY, err
+            );
+            None
         }
-    });
+        Err(_) => None,
+    };
+    let services = Services {
+        metrics,
+        storage,
+        i18n,
+        acl: acl_config,
+        scope_storage,
+    };
+    let Some(client) = http_client(config.proxy.clone()) else {
+        return;
+    };
+    let mut supervisor = Supervisor::new(client, &config);
+    for mut settings in config.bots {
+        match settings.proxy.take() {
+            Some(proxy) => {
+                let Some(client) = http_client(Some(proxy)) else {
+                    return;
+                };
+                supervisor
+                    .add_bot_with_client(settings, &services, client)
+                    .await;
+            }
+            None => {
+                supervisor.add_bot(settings, &services).await;
+            }
+        }
+    }
+    for instance in supervisor.bots() {
+        setup_bot(instance).await;
+    }
+    tokio::spawn(watch_health(supervisor.health(), services.metrics.clone()));
+    supervisor.run().await;
     // ждём, пока не нажмут Ctrl+C
     tokio::signal::ctrl_c().await.unwrap();
 }
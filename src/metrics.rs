@@ -0,0 +1,353 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+/// Upper bounds (in seconds) of the handler latency histogram buckets.
+const LATENCY_BUCKETS: [f64; 12] = [
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0,
+];
+
+#[derive(Default)]
+struct Histogram {
+    buckets: [u64; LATENCY_BUCKETS.len()],
+    sum: f64,
+    count: u64,
+}
+
+impl Histogram {
+    fn observe(&mut self, value: f64) {
+        for (bucket, bound) in self.buckets.iter_mut().zip(LATENCY_BUCKETS) {
+            if value <= bound {
+                *bucket += 1;
+            }
+        }
+        self.sum += value;
+        self.count += 1;
+    }
+}
+
+/// Counts by bot name and label value.
+type Counters = Mutex<HashMap<(String, String), u64>>;
+/// Latencies by bot name and label value.
+type Histograms = Mutex<HashMap<(String, String), Histogram>>;
+
+/// Counters and histograms collected while the bots are running, labeled
+/// by bot so bots sharing a process don't mix.
+/// Rendered in the Prometheus text exposition format by `render`.
+#[derive(Default)]
+pub struct Metrics {
+    updates: Counters,
+    commands: Counters,
+    command_latency: Histograms,
+    command_failures: Counters,
+    events: Counters,
+    event_latency: Histograms,
+    event_failures: Counters,
+    api_calls: Counters,
+    api_errors: Counters,
+    rate_limited: Counters,
+    offsets: Mutex<HashMap<String, i64>>,
+    polling_states: Mutex<HashMap<String, String>>,
+}
+
+fn increment(counters: &Counters, bot: &str, label: &str) {
+    if let Ok(mut counters) = counters.lock() {
+        *counters
+            .entry((bot.to_string(), label.to_string()))
+            .or_default() += 1;
+    }
+}
+
+fn observe(histograms: &Histograms, bot: &str, label: &str, elapsed: Duration) {
+    if let Ok(mut histograms) = histograms.lock() {
+        histograms
+            .entry((bot.to_string(), label.to_string()))
+            .or_default()
+            .observe(elapsed.as_secs_f64());
+    }
+}
+
+/// Labels of a series, e.g. `bot="shop",command="echo"`. An empty `label`
+/// leaves only the bot.
+fn labels(bot: &str, label: &str, value: &str) -> String {
+    match label {
+        "" => format!("bot=\"{}\"", escape(bot)),
+        _ => format!("bot=\"{}\",{label}=\"{}\"", escape(bot), escape(value)),
+    }
+}
+
+fn render_counters(out: &mut String, name: &str, help: &str, label: &str, counters: &Counters) {
+    let _ = writeln!(out, "# HELP {name} {help}");
+    let _ = writeln!(out, "# TYPE {name} counter");
+    if let Ok(counters) = counters.lock() {
+        let mut counters: Vec<_> = counters.iter().collect();
+        counters.sort();
+        for ((bot, value), count) in counters {
+            let _ = writeln!(out, "{name}{{{}}} {count}", labels(bot, label, value));
+        }
+    }
+}
+
+fn render_histograms(
+    out: &mut String,
+    name: &str,
+    help: &str,
+    label: &str,
+    histograms: &Histograms,
+) {
+    let _ = writeln!(out, "# HELP {name} {help}");
+    let _ = writeln!(out, "# TYPE {name} histogram");
+    if let Ok(histograms) = histograms.lock() {
+        let mut histograms: Vec<_> = histograms.iter().collect();
+        histograms.sort_by(|a, b| a.0.cmp(b.0));
+        for ((bot, value), histogram) in histograms {
+            let labels = labels(bot, label, value);
+            for (count, bound) in histogram.buckets.iter().zip(LATENCY_BUCKETS) {
+                let _ = writeln!(out, "{name}_bucket{{{labels},le=\"{bound}\"}} {count}");
+            }
+            let _ = writeln!(
+                out,
+                "{name}_bucket{{{labels},le=\"+Inf\"}} {}",
+                histogram.count
+            );
+            let _ = writeln!(out, "{name}_sum{{{labels}}} {}", histogram.sum);
+            let _ = writeln!(out, "{name}_count{{{labels}}} {}", histogram.count);
+        }
+    }
+}
+
+/// Escapes a label value as required by the text exposition format.
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+impl Metrics {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self::default())
+    }
+
+    /// Counts an update received by the bot, labeled by its kind.
+    pub fn record_update(&self, bot: &str, kind: &str) {
+        increment(&self.updates, bot, kind);
+    }
+
+    /// Counts a dispatched command and records how long its handler took.
+    pub fn record_command(&self, bot: &str, command: &str, elapsed: Duration) {
+        increment(&self.commands, bot, command);
+        observe(&self.command_latency, bot, command, elapsed);
+    }
+
+    /// Counts a command handler that panicked or timed out.
+    pub fn record_command_failure(&self, bot: &str, command: &str) {
+        increment(&self.command_failures, bot, command);
+    }
+
+    /// Counts an event passed to a handler and records how long it took.
+    pub fn record_event(&self, bot: &str, event: &str, elapsed: Duration) {
+        increment(&self.events, bot, event);
+        observe(&self.event_latency, bot, event, elapsed);
+    }
+
+    /// Counts an event handler that panicked or timed out.
+    pub fn record_event_failure(&self, bot: &str, event: &str) {
+        increment(&self.event_failures, bot, event);
+    }
+
+    /// Counts a call to an API method.
+    pub fn record_api_call(&self, bot: &str, method: &str) {
+        increment(&self.api_calls, bot, method);
+    }
+
+    /// Counts a failed call to an API method.
+    pub fn record_api_error(&self, bot: &str, method: &str) {
+        increment(&self.api_errors, bot, method);
+    }
+
+    /// Counts a response with the 429 Too Many Requests error code.
+    pub fn record_rate_limited(&self, bot: &str) {
+        increment(&self.rate_limited, bot, "");
+    }
+
+    pub fn set_offset(&self, bot: &str, offset: i64) {
+        if let Ok(mut offsets) = self.offsets.lock() {
+            offsets.insert(bot.to_string(), offset);
+        }
+    }
+
+    /// Renders all metrics in the Prometheus text format.
+    /// Sets the state of the polling source, e.g. `healthy` or `backoff`.
+    pub fn set_polling_state(&self, bot: &str, state: &str) {
+        if let Ok(mut states) = self.polling_states.lock() {
+            states.insert(bot.to_string(), state.to_string());
+        }
+    }
+
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        render_counters(
+            &mut out,
+            "telegram_updates_total",
+            "Updates received, by kind.",
+            "kind",
+            &self.updates,
+        );
+        render_counters(
+            &mut out,
+            "telegram_commands_total",
+            "Commands dispatched to handlers.",
+            "command",
+            &self.commands,
+        );
+        render_histograms(
+            &mut out,
+            "telegram_handler_duration_seconds",
+            "Time spent in command handlers.",
+            "command",
+            &self.command_latency,
+        );
+        render_counters(
+            &mut out,
+            "telegram_handler_failures_total",
+            "Command handlers that panicked or timed out.",
+            "command",
+            &self.command_failures,
+        );
+        render_counters(
+            &mut out,
+            "telegram_events_total",
+            "Events passed to event handlers, by kind.",
+            "event",
+            &self.events,
+        );
+        render_histograms(
+            &mut out,
+            "telegram_event_handler_duration_seconds",
+            "Time spent in event handlers.",
+            "event",
+            &self.event_latency,
+        );
+        render_counters(
+            &mut out,
+            "telegram_event_handler_failures_total",
+            "Event handlers that panicked or timed out.",
+            "event",
+            &self.event_failures,
+        );
+        render_counters(
+            &mut out,
+            "telegram_api_calls_total",
+            "Bot API calls, by method.",
+            "method",
+            &self.api_calls,
+        );
+        render_counters(
+            &mut out,
+            "telegram_api_errors_total",
+            "Failed Bot API calls, by method.",
+            "method",
+            &self.api_errors,
+        );
+        render_counters(
+            &mut out,
+            "telegram_api_rate_limited_total",
+            "Responses with error code 429.",
+            "",
+            &self.rate_limited,
+        );
+        let name = "telegram_polling_offset";
+        let _ = writeln!(out, "# HELP {name} Current getUpdates offset.");
+        let _ = writeln!(out, "# TYPE {name} gauge");
+        if let Ok(offsets) = self.offsets.lock() {
+            let mut offsets: Vec<_> = offsets.iter().collect();
+            offsets.sort();
+            for (bot, offset) in offsets {
+                let _ = writeln!(out, "{name}{{{}}} {offset}", labels(bot, "", ""));
+            }
+        }
+        let name = "telegram_polling_state";
+        let _ = writeln!(out, "# HELP {name} Current state of the polling source.");
+        let _ = writeln!(out, "# TYPE {name} gauge");
+        if let Ok(states) = self.polling_states.lock() {
+            let mut states: Vec<_> = states.iter().collect();
+            states.sort();
+            for (bot, state) in states {
+                let _ = writeln!(out, "{name}{{{}}} 1", labels(bot, "state", state));
+            }
+        }
+        out
+    }
+
+    /// Serves `GET /metrics` on the given address until the task is dropped.
+    pub async fn serve(self: Arc<Self>, addr: SocketAddr) {
+        let listener = match TcpListener::bind(addr).await {
+            Ok(listener) => listener,
+            Err(err) => {
+                println!("Failed to bind metrics endpoint on {addr}: {err}");
+                return;
+            }
+        };
+        println!("Serving metrics on http://{addr}/metrics");
+        loop {
+            let (mut stream, _) = match listener.accept().await {
+                Ok(conn) => conn,
+                Err(err) => {
+                    println!("Metrics endpoint accept error: {err}");
+                    continue;
+                }
+            };
+            let metrics = self.clone();
+            tokio::spawn(async move {
+                let mut buf = [0u8; 1024];
+                let read = stream.read(&mut buf).await.unwrap_or(0);
+                let request = String::from_utf8_lossy(&buf[..read]);
+                let path = request.split_whitespace().nth(1).unwrap_or_default();
+                let (status, body) = if request.starts_with("GET ") && path == "/metrics" {
+                    ("200 OK", metrics.render())
+                } else {
+                    ("404 Not Found", String::from("Not Found\n"))
+                };
+                let response = format!(
+                    "HTTP/1.1 {status}\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                );
+                let _ = stream.write_all(response.as_bytes()).await;
+                let _ = stream.shutdown().await;
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Metrics;
+    use std::time::Duration;
+
+    #[test]
+    fn keeps_events_and_bots_apart() {
+        let metrics = Metrics::new();
+        metrics.record_command("shop", "echo", Duration::from_millis(3));
+        metrics.record_command("office", "echo", Duration::from_millis(3));
+        metrics.record_event("shop", "poll_answer", Duration::from_millis(3));
+        metrics.set_offset("shop", 42);
+        metrics.set_polling_state("shop", "starting");
+        metrics.set_polling_state("shop", "backoff");
+        let out = metrics.render();
+        assert!(out.contains("telegram_commands_total{bot=\"shop\",command=\"echo\"} 1\n"));
+        assert!(out.contains("telegram_commands_total{bot=\"office\",command=\"echo\"} 1\n"));
+        assert!(out.contains("telegram_events_total{bot=\"shop\",event=\"poll_answer\"} 1\n"));
+        assert!(!out.contains("command=\"poll_answer\""));
+        assert!(out.contains(
+            "telegram_handler_duration_seconds_bucket{bot=\"shop\",command=\"echo\",le=\"0.005\"} 1\n"
+        ));
+        assert!(out.contains("telegram_polling_offset{bot=\"shop\"} 42\n"));
+        assert!(out.contains("telegram_polling_state{bot=\"shop\",state=\"backoff\"} 1\n"));
+        assert!(!out.contains("state=\"starting\""));
+    }
+}
@@ -0,0 +1,90 @@
+use crate::api_client::ApiClient;
+use crate::core::{Bot, Event, EventHandler};
+use crate::http_client::HttpClient;
+use crate::types::{
+    Message, PreCheckoutQuery, RefundedPayment, ShippingOption, ShippingQuery, SuccessfulPayment,
+};
+use async_trait::async_trait;
+use std::sync::Arc;
+
+/// Business logic of a payment flow. Queries are answered by `PaymentFlow`
+/// with whatever the handler returns; errors are shown to the user.
+#[async_trait]
+pub trait PaymentHandler: Send + Sync {
+    /// Shipping options for an invoice with flexible price.
+    async fn shipping(
+        &self,
+        ctx: Arc<Bot>,
+        query: &ShippingQuery,
+    ) -> Result<Vec<ShippingOption>, String> {
+        let _ = (ctx, query);
+        Err(String::from("Shipping is not available."))
+    }
+
+    /// Last chance to reject an order, e.g. if the goods are sold out.
+    async fn pre_checkout(&self, ctx: Arc<Bot>, query: &PreCheckoutQuery) -> Result<(), String>;
+
+    /// Called when the money is received. Deliver the goods here.
+    async fn paid(&self, ctx: Arc<Bot>, message: &Message, payment: &SuccessfulPayment);
+
+    async fn refunded(&self, ctx: Arc<Bot>, message: &Message, refund: &RefundedPayment) {
+        let _ = (ctx, message, refund);
+    }
+}
+
+/// Routes payment events to a `PaymentHandler` and answers the queries.
+/// Register it for the shipping, pre-checkout, successful and refunded
+/// payment event kinds.
+pub struct PaymentFlow<T>
+where
+    T: HttpClient,
+{
+    api_client: Arc<ApiClient<T>>,
+    handler: Arc<dyn PaymentHandler>,
+}
+
+impl<T> PaymentFlow<T>
+where
+    T: HttpClient,
+{
+    pub fn new(api_client: Arc<ApiClient<T>>, handler: Arc<dyn PaymentHandler>) -> Self {
+        PaymentFlow {
+            api_client,
+            handler,
+        }
+    }
+}
+
+#[async_trait]
+impl<T> EventHandler for PaymentFlow<T>
+where
+    T: HttpClient + Send + Sync,
+{
+    async fn handle_event(&self, ctx: Arc<Bot>, event: Event) {
+        match event {
+            Event::ShippingQuery(query) => {
+                let result = self.handler.shipping(ctx, &query).await;
+                self.api_client
+                    .answer_shipping_query(&query.id, result)
+                    .await;
+            }
+            Event::PreCheckoutQuery(query) => {
+                let result = self.handler.pre_checkout(ctx, &query).await;
+                self.api_client
+                    .answer_pre_checkout_query(&query.id, result)
+                    .await;
+            }
+            Event::SuccessfulPayment(message) => {
+                if let Some(payment) = message.successful_payment() {
+                    self.handler.paid(ctx, &message, payment).await;
+                }
+            }
+            Event::RefundedPayment(message) => {
+                if let Some(refund) = message.refunded_payment() {
+                    self.handler.refunded(ctx, &message, refund).await;
+                }
+            }
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,238 @@
+use crate::core::{Bot, Event, EventHandler};
+use crate::storage::{Namespace, Storage};
+use crate::types::{Message, Poll, PollAnswer, PollType};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// Prefix of the storage keys tallies are kept under, in the global namespace.
+const POLL_KEY_PREFIX: &str = "polls.poll.";
+
+/// Key of the last tracked poll, in the namespace of its chat.
+const LAST_POLL_KEY: &str = "polls.last";
+
+/// Collected state of a tracked poll.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PollTally {
+    pub poll_id: String,
+    pub chat_id: i64,
+    pub message_id: i64,
+    pub question: String,
+    pub options: Vec<String>,
+    pub poll_type: PollType,
+    pub correct_option_id: Option<usize>,
+    pub is_anonymous: bool,
+    pub is_closed: bool,
+    /// Voter counts from the latest `poll` update, the only source for anonymous polls
+    pub voter_counts: Vec<i64>,
+    /// Users who voted, from the latest `poll` update if one arrived
+    #[serde(default)]
+    pub total_voter_count: Option<i64>,
+    /// Chosen options by user ID, from `poll_answer` updates
+    pub votes: BTreeMap<i64, Vec<usize>>,
+}
+
+impl PollTally {
+    fn new(message: &Message, poll: &Poll) -> Self {
+        Self {
+            poll_id: poll.id.clone(),
+            chat_id: message.chat.id(),
+            message_id: message.message_id,
+            question: poll.question.clone(),
+            options: poll.options.iter().map(|o| o.text.clone()).collect(),
+            poll_type: poll.poll_type,
+            correct_option_id: poll.correct_option_id,
+            is_anonymous: poll.is_anonymous,
+            is_closed: poll.is_closed,
+            voter_counts: poll.options.iter().map(|o| o.voter_count).collect(),
+            total_voter_count: None,
+            votes: BTreeMap::new(),
+        }
+    }
+
+    /// Number of votes per option. Counted from individual answers when the
+    /// poll is not anonymous, otherwise taken from the poll state.
+    pub fn counts(&self) -> Vec<i64> {
+        if self.is_anonymous {
+            return self.voter_counts.clone();
+        }
+        let mut counts = vec![0; self.options.len()];
+        for option_ids in self.votes.values() {
+            for id in option_ids {
+                if let Some(count) = counts.get_mut(*id) {
+                    *count += 1;
+                }
+            }
+        }
+        counts
+    }
+
+    /// Number of users who voted: from the latest `poll` update if Telegram
+    /// sent one, it also counts retracted votes out. Until then counted from
+    /// the answers, which anonymous polls don't send.
+    pub fn voters(&self) -> i64 {
+        self.total_voter_count.unwrap_or_else(|| {
+            let voters = self.votes.values().filter(|ids| !ids.is_empty()).count();
+            voters as i64
+        })
+    }
+
+    /// Plain text summary, one line per option with votes and percentage.
+    pub fn summary(&self) -> String {
+        let counts = self.counts();
+        let total: i64 = counts.iter().sum();
+        let mut lines = vec![self.question.clone()];
+        for (id, (option, count)) in self.options.iter().zip(&counts).enumerate() {
+            let percent = if total > 0 { count * 100 / total } else { 0 };
+            let mark = if self.correct_option_id == Some(id) {
+                " ✓"
+            } else {
+                ""
+            };
+            lines.push(format!("{option}{mark}: {count} ({percent}%)"));
+        }
+        lines.join("\n")
+    }
+}
+
+/// Keeps tallies of polls sent by the bot in storage. Register it for
+/// `EventKind::Poll` and `EventKind::PollAnswer` and call `track` with the
+/// message returned by `send_poll`.
+pub struct PollTracker {
+    storage: Arc<dyn Storage>,
+    /// Serializes read-modify-write of tallies.
+    lock: Mutex<()>,
+}
+
+impl PollTracker {
+    pub fn new(storage: Arc<dyn Storage>) -> Arc<Self> {
+        Arc::new(Self {
+            storage,
+            lock: Mutex::new(()),
+        })
+    }
+
+    fn key(poll_id: &str) -> String {
+        format!("{POLL_KEY_PREFIX}{poll_id}")
+    }
+
+    /// Starts tracking the poll of a sent message.
+    pub async fn track(&self, message: &Message) -> Option<PollTally> {
+        let tally = PollTally::new(message, message.poll()?);
+        let _guard = self.lock.lock().await;
+        self.storage
+            .set(&Namespace::Global, &Self::key(&tally.poll_id), &tally)
+            .await;
+        self.storage
+            .set(
+                &Namespace::Chat(tally.chat_id),
+                LAST_POLL_KEY,
+                &tally.poll_id,
+            )
+            .await;
+        Some(tally)
+    }
+
+    pub async fn tally(&self, poll_id: &str) -> Option<PollTally> {
+        self.storage
+            .get(&Namespace::Global, &Self::key(poll_id))
+            .await
+    }
+
+    /// The poll tracked last in a chat.
+    pub async fn last_in_chat(&self, chat_id: i64) -> Option<PollTally> {
+        let poll_id: String = self
+            .storage
+            .get(&Namespace::Chat(chat_id), LAST_POLL_KEY)
+            .await?;
+        self.tally(&poll_id).await
+    }
+
+    /// Updates voter counts and the closed flag of a tracked poll.
+    pub async fn record_poll(&self, poll: &Poll) {
+        let _guard = self.lock.lock().await;
+        let Some(mut tally) = self.tally(&poll.id).await else {
+            return;
+        };
+        tally.is_closed = poll.is_closed;
+        tally.voter_counts = poll.options.iter().map(|o| o.voter_count).collect();
+        tally.total_voter_count = Some(poll.total_voter_count);
+        if poll.correct_option_id.is_some() {
+            tally.correct_option_id = poll.correct_option_id;
+        }
+        self.storage
+            .set(&Namespace::Global, &Self::key(&poll.id), &tally)
+            .await;
+    }
+
+    /// Records a vote. An empty answer retracts the previous vote.
+    pub async fn record_answer(&self, answer: &PollAnswer) {
+        let Some(user) = &answer.user else {
+            return;
+        };
+        let _guard = self.lock.lock().await;
+        let Some(mut tally) = self.tally(&answer.poll_id).await else {
+            return;
+        };
+        if answer.option_ids.is_empty() {
+            tally.votes.remove(&user.id);
+        } else {
+            tally.votes.insert(user.id, answer.option_ids.clone());
+        }
+        self.storage
+            .set(&Namespace::Global, &Self::key(&answer.poll_id), &tally)
+            .await;
+    }
+}
+
+#[async_trait]
+impl EventHandler for PollTracker {
+    async fn handle_event(&self, ctx: Arc<Bot>, event: Event) {
+        let _ = ctx;
+        match event {
+            Event::Poll(poll) => self.record_poll(&poll).await,
+            Event::PollAnswer(answer) => self.record_answer(&answer).await,
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PollTracker;
+    use crate::storage::MemoryStorage;
+    use crate::types::fixtures::{message, poll, poll_answer};
+    use std::sync::Arc;
+
+    async fn tracker() -> Arc<PollTracker> {
+        let tracker = PollTracker::new(Arc::new(MemoryStorage::default()));
+        let message = message("").poll(&poll("1", &[0, 0], 0)).build();
+        tracker.track(&message).await.unwrap();
+        tracker
+    }
+
+    #[tokio::test]
+    async fn retracted_votes_are_not_counted() {
+        let tracker = tracker().await;
+        tracker.record_answer(&poll_answer("1", 7, &[0])).await;
+        tracker.record_answer(&poll_answer("1", 8, &[1])).await;
+        tracker.record_answer(&poll_answer("1", 8, &[])).await;
+        let tally = tracker.tally("1").await.unwrap();
+        assert_eq!(tally.voters(), 1);
+        assert_eq!(tally.counts(), [1, 0]);
+
+        // The poll state Telegram sends after the retraction agrees.
+        tracker.record_poll(&poll("1", &[1, 0], 1)).await;
+        assert_eq!(tracker.tally("1").await.unwrap().voters(), 1);
+    }
+
+    #[tokio::test]
+    async fn the_latest_poll_state_wins() {
+        let tracker = tracker().await;
+        tracker.record_poll(&poll("1", &[2, 1], 3)).await;
+        tracker.record_poll(&poll("1", &[1, 1], 2)).await;
+        assert_eq!(tracker.tally("1").await.unwrap().voters(), 2);
+    }
+}
@@ -0,0 +1,6 @@
+pub use crate::scheduler::cron::{format_utc, CronExpr};
+pub use crate::scheduler::job::{Job, JobHandler, Schedule};
+pub use crate::scheduler::runner::{now, Scheduler};
+pub mod cron;
+mod job;
+mod runner;
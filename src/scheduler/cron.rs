@@ -0,0 +1,154 @@
+use std::fmt::Display;
+
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+/// How far ahead `next_after` looks for a matching time.
+const MAX_DAYS_AHEAD: i64 = 4 * 366;
+
+/// A five field cron expression (`minute hour day-of-month month day-of-week`),
+/// evaluated in UTC. Fields accept `*`, numbers, ranges (`1-5`), lists (`1,15`)
+/// and steps (`*/10`, `0-30/5`). Day of week is 0-7, both 0 and 7 are Sunday.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronExpr {
+    source: String,
+    minutes: u64,
+    hours: u64,
+    days_of_month: u64,
+    months: u64,
+    days_of_week: u64,
+    any_day_of_month: bool,
+    any_day_of_week: bool,
+}
+
+fn parse_field(field: &str, min: u32, max: u32) -> Result<u64, String> {
+    let mut mask = 0u64;
+    for item in field.split(',') {
+        let (range, step) = match item.split_once('/') {
+            Some((range, step)) => {
+                let step: u32 = step
+                    .parse()
+                    .map_err(|_| format!("invalid step in <{item}>"))?;
+                if step == 0 {
+                    return Err(format!("zero step in <{item}>"));
+                }
+                (range, step)
+            }
+            None => (item, 1),
+        };
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((start, end)) = range.split_once('-') {
+            let start = start
+                .parse()
+                .map_err(|_| format!("invalid range <{item}>"))?;
+            let end = end.parse().map_err(|_| format!("invalid range <{item}>"))?;
+            (start, end)
+        } else {
+            let value = range
+                .parse()
+                .map_err(|_| format!("invalid value <{item}>"))?;
+            // A single value with a step runs to the end of the range, like `5/15`
+            (value, if step > 1 { max } else { value })
+        };
+        if start < min || end > max || start > end {
+            return Err(format!("<{item}> is out of range {min}-{max}"));
+        }
+        for value in (start..=end).step_by(step as usize) {
+            mask |= 1 << value;
+        }
+    }
+    Ok(mask)
+}
+
+/// Converts days since the Unix epoch into a (year, month, day) civil date.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+impl CronExpr {
+    pub fn parse(expr: &str) -> Result<Self, String> {
+        let fields: Vec<&str> = expr.split_whitespace().collect();
+        let [minute, hour, day_of_month, month, day_of_week] = fields[..] else {
+            return Err(format!(
+                "expected 5 fields in <{expr}>, found {}",
+                fields.len()
+            ));
+        };
+        let mut days_of_week = parse_field(day_of_week, 0, 7)?;
+        if days_of_week & (1 << 7) != 0 {
+            days_of_week |= 1;
+        }
+        Ok(Self {
+            source: fields.join(" "),
+            minutes: parse_field(minute, 0, 59)?,
+            hours: parse_field(hour, 0, 23)?,
+            days_of_month: parse_field(day_of_month, 1, 31)?,
+            months: parse_field(month, 1, 12)?,
+            days_of_week,
+            any_day_of_month: day_of_month.starts_with('*'),
+            any_day_of_week: day_of_week.starts_with('*'),
+        })
+    }
+
+    fn matches_day(&self, days: i64) -> bool {
+        let (_, month, day) = civil_from_days(days);
+        if self.months & (1 << month) == 0 {
+            return false;
+        }
+        // 1970-01-01 was a Thursday
+        let weekday = (days + 4).rem_euclid(7);
+        let dom = self.days_of_month & (1 << day) != 0;
+        let dow = self.days_of_week & (1 << weekday) != 0;
+        // Like in cron, a day matches either field when both are restricted
+        match (self.any_day_of_month, self.any_day_of_week) {
+            (false, false) => dom || dow,
+            _ => dom && dow,
+        }
+    }
+
+    /// Returns the first matching time strictly after `timestamp`, in Unix seconds.
+    pub fn next_after(&self, timestamp: i64) -> Option<i64> {
+        let start = (timestamp.div_euclid(60) + 1) * 60;
+        let first_day = start.div_euclid(SECONDS_PER_DAY);
+        for days in first_day..first_day + MAX_DAYS_AHEAD {
+            if !self.matches_day(days) {
+                continue;
+            }
+            let midnight = days * SECONDS_PER_DAY;
+            for hour in (0..24).filter(|h| self.hours & (1 << h) != 0) {
+                for minute in (0..60).filter(|m| self.minutes & (1 << m) != 0) {
+                    let time = midnight + hour * 3600 + minute * 60;
+                    if time >= start {
+                        return Some(time);
+                    }
+                }
+            }
+        }
+        None
+    }
+}
+
+impl Display for CronExpr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.source)
+    }
+}
+
+/// Formats a Unix time as `YYYY-MM-DD HH:MM UTC`.
+pub fn format_utc(timestamp: i64) -> String {
+    let (year, month, day) = civil_from_days(timestamp.div_euclid(SECONDS_PER_DAY));
+    let seconds = timestamp.rem_euclid(SECONDS_PER_DAY);
+    format!(
+        "{year:04}-{month:02}-{day:02} {:02}:{:02} UTC",
+        seconds / 3600,
+        seconds % 3600 / 60
+    )
+}
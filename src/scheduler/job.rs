@@ -0,0 +1,51 @@
+use crate::scheduler::cron::CronExpr;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+/// When a job runs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Schedule {
+    /// Runs once at the given Unix time.
+    Once { at: i64 },
+    /// Runs every time the cron expression matches.
+    Cron { expr: String },
+}
+
+impl Schedule {
+    /// Creates a cron schedule, validating the expression.
+    pub fn cron(expr: &str) -> Result<Self, String> {
+        let expr = CronExpr::parse(expr)?;
+        Ok(Schedule::Cron {
+            expr: expr.to_string(),
+        })
+    }
+
+    /// Next run strictly after `now`, or `None` if the job will not run again.
+    pub fn next_after(&self, now: i64) -> Option<i64> {
+        match self {
+            Schedule::Once { at } if *at > now => Some(*at),
+            Schedule::Once { .. } => None,
+            Schedule::Cron { expr } => CronExpr::parse(expr).ok()?.next_after(now),
+        }
+    }
+}
+
+/// A scheduled job. `kind` selects the `JobHandler` that runs it,
+/// `payload` is handler specific data.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Job {
+    pub id: u64,
+    pub chat_id: i64,
+    pub schedule: Schedule,
+    pub kind: String,
+    pub payload: serde_json::Value,
+    /// Unix time of the next run.
+    pub next_run: i64,
+}
+
+/// Runs jobs of one kind.
+#[async_trait]
+pub trait JobHandler: Send + Sync {
+    async fn run(&self, job: &Job);
+}
@@ -0,0 +1,283 @@
+use crate::scheduler::job::{Job, JobHandler, Schedule};
+use crate::storage::{Namespace, Storage};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::{Notify, RwLock};
+
+/// Prefix of the storage keys jobs are kept under, in the global namespace.
+const JOB_KEY_PREFIX: &str = "scheduler.job.";
+
+/// Longest time the scheduler sleeps before checking for due jobs again.
+const MAX_SLEEP_SECS: i64 = 60;
+
+/// Current Unix time in seconds.
+pub fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or_default()
+}
+
+/// Runs one-off and recurring jobs next to the updates loop.
+pub struct Scheduler {
+    jobs: RwLock<HashMap<u64, Job>>,
+    handlers: RwLock<HashMap<String, Arc<dyn JobHandler>>>,
+    storage: Arc<dyn Storage>,
+    next_id: AtomicU64,
+    wake: Notify,
+}
+
+impl Scheduler {
+    pub fn new(storage: Arc<dyn Storage>) -> Arc<Self> {
+        Arc::new(Self {
+            jobs: RwLock::new(HashMap::new()),
+            handlers: RwLock::new(HashMap::new()),
+            storage,
+            next_id: AtomicU64::new(1),
+            wake: Notify::new(),
+        })
+    }
+
+    /// Loads persisted jobs. Jobs that were due while the bot was down run right away.
+    pub async fn load(&self) {
+        let loaded = self
+            .storage
+            .scan::<Job>(&Namespace::Global, JOB_KEY_PREFIX)
+            .await;
+        let mut jobs = self.jobs.write().await;
+        for (_, job) in loaded {
+            self.next_id.fetch_max(job.id + 1, Ordering::Relaxed);
+            jobs.insert(job.id, job);
+        }
+        println!("Loaded {} scheduled jobs", jobs.len());
+    }
+
+    /// Registers the handler that runs jobs of the given kind.
+    pub async fn register_handler(&self, kind: &str, handler: Arc<dyn JobHandler>) {
+        self.handlers
+            .write()
+            .await
+            .insert(kind.to_string(), handler);
+    }
+
+    /// Adds a job and returns its ID.
+    pub async fn schedule(
+        &self,
+        chat_id: i64,
+        schedule: Schedule,
+        kind: &str,
+        payload: serde_json::Value,
+    ) -> Result<u64, String> {
+        let next_run = schedule
+            .next_after(now())
+            .ok_or_else(|| String::from("the job would never run"))?;
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let job = Job {
+            id,
+            chat_id,
+            schedule,
+            kind: kind.to_string(),
+            payload,
+            next_run,
+        };
+        self.save(&job).await;
+        self.jobs.write().await.insert(id, job);
+        self.wake.notify_one();
+        Ok(id)
+    }
+
+    /// Cancels a job of the given chat. Returns false if there is no such job.
+    pub async fn cancel(&self, chat_id: i64, id: u64) -> bool {
+        let mut jobs = self.jobs.write().await;
+        match jobs.get(&id) {
+            Some(job) if job.chat_id == chat_id => {
+                jobs.remove(&id);
+                self.storage
+                    .delete(&Namespace::Global, &Self::key(id))
+                    .await;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Jobs of the given chat, the soonest first.
+    pub async fn jobs_for_chat(&self, chat_id: i64) -> Vec<Job> {
+        let mut jobs: Vec<Job> = self
+            .jobs
+            .read()
+            .await
+            .values()
+            .filter(|job| job.chat_id == chat_id)
+            .cloned()
+            .collect();
+        jobs.sort_by_key(|job| job.next_run);
+        jobs
+    }
+
+    fn key(id: u64) -> String {
+        format!("{JOB_KEY_PREFIX}{id}")
+    }
+
+    async fn save(&self, job: &Job) {
+        self.storage
+            .set(&Namespace::Global, &Self::key(job.id), job)
+            .await;
+    }
+
+    /// Starts due jobs and moves them to their next run.
+    async fn run_due(&self, now: i64) {
+        let due: Vec<Job> = {
+            let mut jobs = self.jobs.write().await;
+            let due: Vec<Job> = jobs
+                .values()
+                .filter(|job| job.next_run <= now)
+                .cloned()
+                .collect();
+            // Stored while holding the lock, so a job cancelled meanwhile
+            // can't be written back.
+            for job in &due {
+                match job.schedule.next_after(now) {
+                    Some(next_run) => {
+                        if let Some(job) = jobs.get_mut(&job.id) {
+                            job.next_run = next_run;
+                            let job = job.clone();
+                            self.save(&job).await;
+                        }
+                    }
+                    None => {
+                        jobs.remove(&job.id);
+                        self.storage
+                            .delete(&Namespace::Global, &Self::key(job.id))
+                            .await;
+                    }
+                }
+            }
+            due
+        };
+        if due.is_empty() {
+            return;
+        }
+        let handlers = self.handlers.read().await;
+        for job in due {
+            match handlers.get(&job.kind) {
+                Some(handler) => {
+                    let handler = handler.clone();
+                    tokio::spawn(async move { handler.run(&job).await });
+                }
+                None => println!("No handler for job {} of kind <{}>", job.id, job.kind),
+            }
+        }
+    }
+
+    /// Runs jobs as they become due. Never returns.
+    pub async fn run(self: Arc<Self>) {
+        loop {
+            let now = now();
+            self.run_due(now).await;
+            let next_run = self
+                .jobs
+                .read()
+                .await
+                .values()
+                .map(|job| job.next_run)
+                .min();
+            let sleep = next_run
+                .map(|next_run| next_run - now)
+                .unwrap_or(MAX_SLEEP_SECS)
+                .clamp(1, MAX_SLEEP_SECS);
+            tokio::select! {
+                _ = tokio::time::sleep(Duration::from_secs(sleep as u64)) => {}
+                _ = self.wake.notified() => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{now, Scheduler, JOB_KEY_PREFIX};
+    use crate::scheduler::Schedule;
+    use crate::storage::{MemoryStorage, Namespace, Storage, ValueUpdate};
+    use async_trait::async_trait;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+    use tokio::sync::Notify;
+
+    /// Memory storage whose writes wait for `release` while `closed` is set.
+    #[derive(Default)]
+    struct GatedStorage {
+        inner: MemoryStorage,
+        closed: AtomicBool,
+        writing: Notify,
+        release: Notify,
+    }
+
+    #[async_trait]
+    impl Storage for GatedStorage {
+        async fn get_value(&self, ns: &Namespace, key: &str) -> Option<serde_json::Value> {
+            self.inner.get_value(ns, key).await
+        }
+
+        async fn set_value(&self, ns: &Namespace, key: &str, value: serde_json::Value) {
+            if self.closed.swap(false, Ordering::SeqCst) {
+                self.writing.notify_one();
+                self.release.notified().await;
+            }
+            self.inner.set_value(ns, key, value).await
+        }
+
+        async fn delete(&self, ns: &Namespace, key: &str) -> bool {
+            self.inner.delete(ns, key).await
+        }
+
+        async fn scan_values(
+            &self,
+            ns: &Namespace,
+            prefix: &str,
+        ) -> Vec<(String, serde_json::Value)> {
+            self.inner.scan_values(ns, prefix).await
+        }
+
+        async fn update_value(&self, ns: &Namespace, key: &str, update: ValueUpdate) {
+            self.inner.update_value(ns, key, update).await
+        }
+    }
+
+    #[tokio::test]
+    async fn cancelled_jobs_stay_cancelled() {
+        let storage = Arc::new(GatedStorage::default());
+        let scheduler = Scheduler::new(storage.clone());
+        let schedule = Schedule::cron("* * * * *").unwrap();
+        let id = scheduler
+            .schedule(1, schedule, "test", serde_json::Value::Null)
+            .await
+            .unwrap();
+
+        // Cancel while the run stores the job's next run.
+        storage.closed.store(true, Ordering::SeqCst);
+        let run = tokio::spawn({
+            let scheduler = scheduler.clone();
+            async move { scheduler.run_due(now() + 120).await }
+        });
+        storage.writing.notified().await;
+        let cancel = tokio::spawn({
+            let scheduler = scheduler.clone();
+            async move { scheduler.cancel(1, id).await }
+        });
+        for _ in 0..10 {
+            tokio::task::yield_now().await;
+        }
+        storage.release.notify_one();
+        run.await.unwrap();
+        assert!(cancel.await.unwrap());
+
+        let stored = storage
+            .scan_values(&Namespace::Global, JOB_KEY_PREFIX)
+            .await;
+        assert!(stored.is_empty());
+        assert!(scheduler.jobs_for_chat(1).await.is_empty());
+    }
+}
@@ -0,0 +1,156 @@
+#[cfg(feature = "json-file")]
+pub use crate::storage::json_file::JsonFileStorage;
+pub use crate::storage::memory::MemoryStorage;
+pub use crate::storage::scoped::ScopedStorage;
+#[cfg(feature = "sqlite")]
+pub use crate::storage::sqlite::SqliteStorage;
+use async_trait::async_trait;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fmt::Display;
+use std::sync::{Arc, Mutex};
+
+#[cfg(feature = "json-file")]
+mod json_file;
+mod memory;
+mod scoped;
+#[cfg(feature = "sqlite")]
+mod sqlite;
+
+/// Scope of a stored key, so chats and users never see each other's data.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Namespace {
+    Global,
+    Chat(i64),
+    User(i64),
+}
+
+impl Display for Namespace {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Namespace::Global => write!(f, "global"),
+            Namespace::Chat(id) => write!(f, "chat:{id}"),
+            Namespace::User(id) => write!(f, "user:{id}"),
+        }
+    }
+}
+
+/// Computes the new value of a key from its current value, see `Storage::update_value`.
+pub type ValueUpdate = Box<dyn FnOnce(Option<serde_json::Value>) -> serde_json::Value + Send>;
+
+/// Key-value storage for bot state. Values are JSON, use the typed
+/// `get`, `set` and `scan` helpers on `dyn Storage` to work with Rust types.
+#[async_trait]
+pub trait Storage: Send + Sync {
+    async fn get_value(&self, ns: &Namespace, key: &str) -> Option<serde_json::Value>;
+    async fn set_value(&self, ns: &Namespace, key: &str, value: serde_json::Value);
+    /// Removes the key, returns false if it was not set.
+    async fn delete(&self, ns: &Namespace, key: &str) -> bool;
+    /// All keys of the namespace starting with `prefix`, sorted by key.
+    async fn scan_values(&self, ns: &Namespace, prefix: &str) -> Vec<(String, serde_json::Value)>;
+    /// Replaces the value of the key with `update(current)`, with no other
+    /// write to the key in between. SQLite also guarantees this across
+    /// processes sharing the database, the other backends only within one
+    /// storage object.
+    async fn update_value(&self, ns: &Namespace, key: &str, update: ValueUpdate);
+}
+
+impl dyn Storage {
+    pub async fn get<V>(&self, ns: &Namespace, key: &str) -> Option<V>
+    where
+        V: DeserializeOwned,
+    {
+        let value = self.get_value(ns, key).await?;
+        serde_json::from_value(value)
+            .map_err(|err| println!("Error parsing stored {ns}/{key}: {err}"))
+            .ok()
+    }
+
+    pub async fn set<V>(&self, ns: &Namespace, key: &str, value: &V)
+    where
+        V: Serialize + Sync,
+    {
+        match serde_json::to_value(value) {
+            Ok(value) => self.set_value(ns, key, value).await,
+            Err(err) => println!("Error serializing {ns}/{key}: {err}"),
+        }
+    }
+
+    /// Atomically applies `update` to the stored value, starting from the
+    /// default if the key is not set. Returns what `update` returned, or
+    /// `None` if the storage failed before running it.
+    pub async fn update<V, R, F>(&self, ns: &Namespace, key: &str, update: F) -> Option<R>
+    where
+        V: Serialize + DeserializeOwned + Default,
+        R: Send + 'static,
+        F: FnOnce(&mut V) -> R + Send + 'static,
+    {
+        let result = Arc::new(Mutex::new(None));
+        let slot = result.clone();
+        let name = format!("{ns}/{key}");
+        self.update_value(
+            ns,
+            key,
+            Box::new(move |current| {
+                let mut value = match current.clone().map(serde_json::from_value) {
+                    Some(Ok(value)) => value,
+                    Some(Err(err)) => {
+                        println!("Error parsing stored {name}: {err}");
+                        V::default()
+                    }
+                    None => V::default(),
+                };
+                let returned = update(&mut value);
+                match serde_json::to_value(&value) {
+                    Ok(value) => {
+                        if let Ok(mut slot) = slot.lock() {
+                            *slot = Some(returned);
+                        }
+                        value
+                    }
+                    Err(err) => {
+                        println!("Error serializing {name}: {err}");
+                        current.unwrap_or_default()
+                    }
+                }
+            }),
+        )
+        .await;
+        let returned = result.lock().ok()?.take();
+        returned
+    }
+
+    pub async fn scan<V>(&self, ns: &Namespace, prefix: &str) -> Vec<(String, V)>
+    where
+        V: DeserializeOwned,
+    {
+        self.scan_values(ns, prefix)
+            .await
+            .into_iter()
+            .filter_map(|(key, value)| match serde_json::from_value(value) {
+                Ok(value) => Some((key, value)),
+                Err(err) => {
+                    println!("Error parsing stored {ns}/{key}: {err}");
+                    None
+                }
+            })
+            .collect()
+    }
+}
+
+/// Opens the storage described by `spec`: `memory`, `json:<path>` or `sqlite:<path>`.
+pub fn open(spec: &str) -> Option<Arc<dyn Storage>> {
+    let (kind, path) = spec.split_once(':').unwrap_or((spec, ""));
+    match kind {
+        "memory" => Some(Arc::new(MemoryStorage::default())),
+        #[cfg(feature = "json-file")]
+        "json" => Some(Arc::new(JsonFileStorage::open(path)?)),
+        #[cfg(feature = "sqlite")]
+        "sqlite" => Some(Arc::new(SqliteStorage::open(path)?)),
+        _ => {
+            println!("Unsupported storage <{spec}>, check the enabled features");
+            let _ = path;
+            None
+        }
+    }
+}
@@ -0,0 +1,107 @@
+use crate::storage::{Namespace, Storage, ValueUpdate};
+use async_trait::async_trait;
+use std::collections::BTreeMap;
+use tokio::sync::Mutex;
+
+type Data = BTreeMap<String, BTreeMap<String, serde_json::Value>>;
+
+/// Keeps everything in memory and rewrites a JSON file on every change.
+/// Fine for small bots, use SQLite for anything busy.
+pub struct JsonFileStorage {
+    path: String,
+    data: Mutex<Data>,
+}
+
+impl JsonFileStorage {
+    /// Opens the file, starting empty if it does not exist yet.
+    pub fn open(path: &str) -> Option<Self> {
+        let data = match std::fs::read_to_string(path) {
+            Ok(content) => serde_json::from_str(&content)
+                .map_err(|err| println!("Error parsing storage file {path}: {err}"))
+                .ok()?,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Data::new(),
+            Err(err) => {
+                println!("Error reading storage file {path}: {err}");
+                return None;
+            }
+        };
+        Some(Self {
+            path: path.to_string(),
+            data: Mutex::new(data),
+        })
+    }
+
+    async fn save(&self, data: &Data) {
+        let content = match serde_json::to_string(data) {
+            Ok(content) => content,
+            Err(err) => {
+                println!("Error serializing storage: {err}");
+                return;
+            }
+        };
+        // Write to a temporary file first so a crash never leaves a truncated file
+        let tmp = format!("{}.tmp", self.path);
+        if let Err(err) = tokio::fs::write(&tmp, content).await {
+            println!("Error writing storage to {tmp}: {err}");
+            return;
+        }
+        if let Err(err) = tokio::fs::rename(&tmp, &self.path).await {
+            println!("Error saving storage to {}: {err}", self.path);
+        }
+    }
+}
+
+#[async_trait]
+impl Storage for JsonFileStorage {
+    async fn get_value(&self, ns: &Namespace, key: &str) -> Option<serde_json::Value> {
+        self.data
+            .lock()
+            .await
+            .get(&ns.to_string())?
+            .get(key)
+            .cloned()
+    }
+
+    async fn set_value(&self, ns: &Namespace, key: &str, value: serde_json::Value) {
+        let mut data = self.data.lock().await;
+        data.entry(ns.to_string())
+            .or_default()
+            .insert(key.to_string(), value);
+        self.save(&data).await;
+    }
+
+    async fn delete(&self, ns: &Namespace, key: &str) -> bool {
+        let mut data = self.data.lock().await;
+        let ns = ns.to_string();
+        let Some(values) = data.get_mut(&ns) else {
+            return false;
+        };
+        if values.remove(key).is_none() {
+            return false;
+        }
+        if values.is_empty() {
+            data.remove(&ns);
+        }
+        self.save(&data).await;
+        true
+    }
+
+    async fn scan_values(&self, ns: &Namespace, prefix: &str) -> Vec<(String, serde_json::Value)> {
+        match self.data.lock().await.get(&ns.to_string()) {
+            Some(values) => values
+                .range(prefix.to_string()..)
+                .take_while(|(key, _)| key.starts_with(prefix))
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    async fn update_value(&self, ns: &Namespace, key: &str, update: ValueUpdate) {
+        let mut data = self.data.lock().await;
+        let values = data.entry(ns.to_string()).or_default();
+        let value = update(values.get(key).cloned());
+        values.insert(key.to_string(), value);
+        self.save(&data).await;
+    }
+}
@@ -0,0 +1,56 @@
+use crate::storage::{Namespace, Storage, ValueUpdate};
+use async_trait::async_trait;
+use std::collections::{BTreeMap, HashMap};
+use tokio::sync::RwLock;
+
+/// Keeps everything in memory, state is lost on restart.
+#[derive(Default)]
+pub struct MemoryStorage {
+    data: RwLock<HashMap<Namespace, BTreeMap<String, serde_json::Value>>>,
+}
+
+#[async_trait]
+impl Storage for MemoryStorage {
+    async fn get_value(&self, ns: &Namespace, key: &str) -> Option<serde_json::Value> {
+        self.data.read().await.get(ns)?.get(key).cloned()
+    }
+
+    async fn set_value(&self, ns: &Namespace, key: &str, value: serde_json::Value) {
+        self.data
+            .write()
+            .await
+            .entry(ns.clone())
+            .or_default()
+            .insert(key.to_string(), value);
+    }
+
+    async fn delete(&self, ns: &Namespace, key: &str) -> bool {
+        let mut data = self.data.write().await;
+        let Some(values) = data.get_mut(ns) else {
+            return false;
+        };
+        let removed = values.remove(key).is_some();
+        if values.is_empty() {
+            data.remove(ns);
+        }
+        removed
+    }
+
+    async fn scan_values(&self, ns: &Namespace, prefix: &str) -> Vec<(String, serde_json::Value)> {
+        match self.data.read().await.get(ns) {
+            Some(values) => values
+                .range(prefix.to_string()..)
+                .take_while(|(key, _)| key.starts_with(prefix))
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    async fn update_value(&self, ns: &Namespace, key: &str, update: ValueUpdate) {
+        let mut data = self.data.write().await;
+        let values = data.entry(ns.clone()).or_default();
+        let value = update(values.get(key).cloned());
+        values.insert(key.to_string(), value);
+    }
+}
@@ -0,0 +1,51 @@
+use crate::storage::{Namespace, Storage, ValueUpdate};
+use async_trait::async_trait;
+use std::sync::Arc;
+
+/// Prefixes every key with a scope, so several bots can share one backend
+/// without seeing each other's state.
+pub struct ScopedStorage {
+    inner: Arc<dyn Storage>,
+    prefix: String,
+}
+
+impl ScopedStorage {
+    pub fn new(inner: Arc<dyn Storage>, scope: &str) -> Self {
+        Self {
+            inner,
+            prefix: format!("{scope}/"),
+        }
+    }
+
+    fn key(&self, key: &str) -> String {
+        format!("{}{key}", self.prefix)
+    }
+}
+
+#[async_trait]
+impl Storage for ScopedStorage {
+    async fn get_value(&self, ns: &Namespace, key: &str) -> Option<serde_json::Value> {
+        self.inner.get_value(ns, &self.key(key)).await
+    }
+
+    async fn set_value(&self, ns: &Namespace, key: &str, value: serde_json::Value) {
+        self.inner.set_value(ns, &self.key(key), value).await
+    }
+
+    async fn delete(&self, ns: &Namespace, key: &str) -> bool {
+        self.inner.delete(ns, &self.key(key)).await
+    }
+
+    async fn scan_values(&self, ns: &Namespace, prefix: &str) -> Vec<(String, serde_json::Value)> {
+        self.inner
+            .scan_values(ns, &self.key(prefix))
+            .await
+            .into_iter()
+            .filter_map(|(key, value)| Some((key.strip_prefix(&self.prefix)?.to_string(), value)))
+            .collect()
+    }
+
+    async fn update_value(&self, ns: &Namespace, key: &str, update: ValueUpdate) {
+        self.inner.update_value(ns, &self.key(key), update).await
+    }
+}
@@ -0,0 +1,170 @@
+use crate::storage::{Namespace, Storage, ValueUpdate};
+use async_trait::async_trait;
+use rusqlite::{params, Connection, OptionalExtension, Transaction, TransactionBehavior};
+use std::sync::{Arc, Mutex};
+
+/// Stores values in an SQLite database, one row per key.
+pub struct SqliteStorage {
+    connection: Arc<Mutex<Connection>>,
+}
+
+impl SqliteStorage {
+    pub fn open(path: &str) -> Option<Self> {
+        let connection = Connection::open(path)
+            .map_err(|err| println!("Error opening SQLite storage {path}: {err}"))
+            .ok()?;
+        connection
+            .execute_batch(
+                "CREATE TABLE IF NOT EXISTS kv (
+                    namespace TEXT NOT NULL,
+                    key TEXT NOT NULL,
+                    value TEXT NOT NULL,
+                    PRIMARY KEY (namespace, key)
+                );",
+            )
+            .map_err(|err| println!("Error creating SQLite storage schema: {err}"))
+            .ok()?;
+        Some(Self {
+            connection: Arc::new(Mutex::new(connection)),
+        })
+    }
+
+    /// Runs a query on the blocking thread pool, SQLite calls block.
+    async fn with_connection<F, R>(&self, query: F) -> Option<R>
+    where
+        F: FnOnce(&Connection) -> rusqlite::Result<R> + Send + 'static,
+        R: Send + 'static,
+    {
+        let connection = self.connection.clone();
+        tokio::task::spawn_blocking(move || {
+            let connection = connection.lock().ok()?;
+            query(&connection)
+                .map_err(|err| println!("SQLite storage error: {err}"))
+                .ok()
+        })
+        .await
+        .ok()
+        .flatten()
+    }
+}
+
+#[async_trait]
+impl Storage for SqliteStorage {
+    async fn get_value(&self, ns: &Namespace, key: &str) -> Option<serde_json::Value> {
+        let (ns, key) = (ns.to_string(), key.to_string());
+        let value: String = self
+            .with_connection(move |conn| {
+                conn.query_row(
+                    "SELECT value FROM kv WHERE namespace = ?1 AND key = ?2",
+                    params![ns, key],
+                    |row| row.get(0),
+                )
+                .optional()
+            })
+            .await??;
+        serde_json::from_str(&value).ok()
+    }
+
+    async fn set_value(&self, ns: &Namespace, key: &str, value: serde_json::Value) {
+        let (ns, key, value) = (ns.to_string(), key.to_string(), value.to_string());
+        self.with_connection(move |conn| {
+            conn.execute(
+                "INSERT INTO kv (namespace, key, value) VALUES (?1, ?2, ?3)
+                 ON CONFLICT (namespace, key) DO UPDATE SET value = excluded.value",
+                params![ns, key, value],
+            )
+        })
+        .await;
+    }
+
+    async fn delete(&self, ns: &Namespace, key: &str) -> bool {
+        let (ns, key) = (ns.to_string(), key.to_string());
+        self.with_connection(move |conn| {
+            conn.execute(
+                "DELETE FROM kv WHERE namespace = ?1 AND key = ?2",
+                params![ns, key],
+            )
+        })
+        .await
+        .is_some_and(|deleted| deleted > 0)
+    }
+
+    async fn scan_values(&self, ns: &Namespace, prefix: &str) -> Vec<(String, serde_json::Value)> {
+        let (ns, prefix) = (ns.to_string(), prefix.to_string());
+        let rows = self
+            .with_connection(move |conn| {
+                // substr instead of LIKE, so `%` and `_` in the prefix are literal
+                let mut statement = conn.prepare(
+                    "SELECT key, value FROM kv
+                     WHERE namespace = ?1 AND substr(key, 1, length(?2)) = ?2
+                     ORDER BY key",
+                )?;
+                let rows = statement
+                    .query_map(params![ns, prefix], |row| {
+                        Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+                    })?
+                    .collect::<rusqlite::Result<Vec<_>>>()?;
+                Ok(rows)
+            })
+            .await
+            .unwrap_or_default();
+        rows.into_iter()
+            .filter_map(|(key, value)| Some((key, serde_json::from_str(&value).ok()?)))
+            .collect()
+    }
+
+    async fn update_value(&self, ns: &Namespace, key: &str, update: ValueUpdate) {
+        let (ns, key) = (ns.to_string(), key.to_string());
+        self.with_connection(move |conn| {
+            // IMMEDIATE takes the write lock before reading, so other
+            // processes can't change the row between the read and the write
+            let tx = Transaction::new_unchecked(conn, TransactionBehavior::Immediate)?;
+            let current: Option<String> = tx
+                .query_row(
+                    "SELECT value FROM kv WHERE namespace = ?1 AND key = ?2",
+                    params![ns, key],
+                    |row| row.get(0),
+                )
+                .optional()?;
+            let value = update(current.and_then(|value| serde_json::from_str(&value).ok()));
+            tx.execute(
+                "INSERT INTO kv (namespace, key, value) VALUES (?1, ?2, ?3)
+                 ON CONFLICT (namespace, key) DO UPDATE SET value = excluded.value",
+                params![ns, key, value.to_string()],
+            )?;
+            tx.commit()
+        })
+        .await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SqliteStorage;
+    use crate::storage::{Namespace, Storage};
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn updates_are_atomic_across_connections() {
+        let path = std::env::temp_dir().join(format!("storage-{}.db", std::process::id()));
+        let path = path.to_str().unwrap();
+        let first: Arc<dyn Storage> = Arc::new(SqliteStorage::open(path).unwrap());
+        let second: Arc<dyn Storage> = Arc::new(SqliteStorage::open(path).unwrap());
+        let tasks: Vec<_> = (0..40)
+            .map(|i| {
+                let storage = if i % 2 == 0 { &first } else { &second }.clone();
+                tokio::spawn(async move {
+                    storage
+                        .update(&Namespace::Global, "counter", |count: &mut u32| *count += 1)
+                        .await
+                })
+            })
+            .collect();
+        for task in tasks {
+            assert_eq!(task.await.unwrap(), Some(()));
+        }
+        let count: Option<u32> = first.get(&Namespace::Global, "counter").await;
+        std::fs::remove_file(path).unwrap();
+        assert_eq!(count, Some(40));
+    }
+}
@@ -0,0 +1,13 @@
+use crate::core::{Bot, Incoming};
+pub use crate::supervisor::config::{BotSettings, SupervisorConfig, UpdateSource};
+pub use crate::supervisor::polling::{Backoff, PollingHealth, SourceHealth};
+pub use crate::supervisor::runner::{BotClients, BotInstance, Services, Supervisor};
+use std::sync::Arc;
+
+mod config;
+mod polling;
+mod runner;
+mod webhook;
+
+/// A routed update together with the bot it was sent to, waiting for a worker.
+pub type Delivery = (Arc<Bot>, Incoming);
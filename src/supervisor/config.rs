@@ -0,0 +1,197 @@
+use crate::core::DEFAULT_HANDLER_TIMEOUT;
+use crate::http_client::ProxySettings;
+use crate::supervisor::webhook::path_of;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::SocketAddr;
+
+/// How a bot receives updates.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum UpdateSource {
+    /// Long polling with `getUpdates`
+    #[default]
+    Polling,
+    /// Telegram posts updates to `url`, served on the supervisor's `webhook_addr`.
+    /// The path of the URL tells the bots apart.
+    Webhook {
+        url: String,
+        #[serde(default)]
+        secret_token: Option<String>,
+    },
+}
+
+/// Settings of one bot.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BotSettings {
+    /// Unique name, used in logs and to scope the bot's storage
+    pub name: String,
+    pub token: String,
+    #[serde(default)]
+    pub source: UpdateSource,
+    #[serde(default = "BotSettings::default_base_url")]
+    pub base_url: String,
+    #[serde(default = "BotSettings::default_polling_timeout")]
+    pub polling_timeout: i64,
+    /// `base_url` is a self-hosted Bot API server started with `--local`
+    #[serde(default)]
+    pub local_mode: bool,
+    /// Update types to receive. By default they follow the registered handlers.
+    #[serde(default)]
+    pub allowed_updates: Option<Vec<String>>,
+    /// Most updates fetched by one `getUpdates` call, 1-100
+    #[serde(default)]
+    pub limit: Option<u32>,
+    /// Proxy for this bot instead of the supervisor's one. The bot then
+    /// gets its own HTTP client.
+    #[serde(default)]
+    pub proxy: Option<ProxySettings>,
+}
+
+impl BotSettings {
+    fn default_base_url() -> String {
+        String::from("https://api.telegram.org")
+    }
+
+    fn default_polling_timeout() -> i64 {
+        30
+    }
+
+    /// A polling bot with default settings.
+    pub fn new(name: &str, token: String) -> Self {
+        Self {
+            name: name.to_string(),
+            token,
+            source: UpdateSource::Polling,
+            base_url: Self::default_base_url(),
+            polling_timeout: Self::default_polling_timeout(),
+            local_mode: false,
+            allowed_updates: None,
+            limit: None,
+            proxy: None,
+        }
+    }
+
+    /// Talks to a self-hosted Bot API server in local mode instead of the cloud.
+    pub fn local_server(mut self, base_url: String) -> Self {
+        self.base_url = base_url;
+        self.local_mode = true;
+        self
+    }
+}
+
+/// Bots hosted by the process, usually read from the JSON file set in `BOTS_CONFIG`:
+///
+/// ```json
+/// {
+///     "workers": 8,
+///     "handler_timeout": 300,
+///     "webhook_addr": "0.0.0.0:8443",
+///     "proxy": { "url": "socks5h://127.0.0.1:1080", "username": "bot", "password": "secret" },
+///     "bots": [
+///         { "name": "echo", "token": "123:abc", "limit": 50 },
+///         {
+///             "name": "shop",
+///             "token": "456:def",
+///             "source": { "type": "webhook", "url": "https://example.com/shop", "secret_token": "s3cret" }
+///         },
+///         { "name": "files", "token": "789:ghi", "base_url": "http://localhost:8081", "local_mode": true },
+///         { "name": "office", "token": "012:jkl", "proxy": { "url": "http://proxy.lan:3128" } }
+///     ]
+/// }
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SupervisorConfig {
+    pub bots: Vec<BotSettings>,
+    /// Number of tasks handling updates of all bots
+    #[serde(default = "SupervisorConfig::default_workers")]
+    pub workers: usize,
+    /// Address the webhook server listens on, required if any bot uses a webhook
+    #[serde(default)]
+    pub webhook_addr: Option<SocketAddr>,
+    /// Proxy of the shared HTTP client; the environment variables apply if unset
+    #[serde(default)]
+    pub proxy: Option<ProxySettings>,
+    /// Seconds a handler may run before it is cancelled
+    #[serde(default = "SupervisorConfig::default_handler_timeout")]
+    pub handler_timeout: u64,
+}
+
+impl SupervisorConfig {
+    fn default_workers() -> usize {
+        4
+    }
+
+    fn default_handler_timeout() -> u64 {
+        DEFAULT_HANDLER_TIMEOUT.as_secs()
+    }
+
+    /// Configuration of a single polling bot.
+    pub fn single(bot: BotSettings) -> Self {
+        Self {
+            bots: vec![bot],
+            workers: Self::default_workers(),
+            webhook_addr: None,
+            proxy: None,
+            handler_timeout: Self::default_handler_timeout(),
+        }
+    }
+
+    pub fn from_file(path: &str) -> Option<Self> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|err| println!("Error reading bots config {path}: {err}"))
+            .ok()?;
+        let config: Self = serde_json::from_str(&content)
+            .map_err(|err| println!("Error parsing bots config {path}: {err}"))
+            .ok()?;
+        config
+            .validate()
+            .map_err(|err| println!("Error in bots config {path}: {err}"))
+            .ok()?;
+        Some(config)
+    }
+
+    /// Checks that webhook bots don't share a path, the server couldn't
+    /// tell their updates apart.
+    pub fn validate(&self) -> Result<(), String> {
+        let mut paths = HashMap::new();
+        for bot in &self.bots {
+            let UpdateSource::Webhook { url, .. } = &bot.source else {
+                continue;
+            };
+            if let Some(other) = paths.insert(path_of(url), &bot.name) {
+                return Err(format!(
+                    "bots {other} and {} use the same webhook path {}",
+                    bot.name,
+                    path_of(url)
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BotSettings, SupervisorConfig, UpdateSource};
+
+    fn webhook(name: &str, url: &str) -> BotSettings {
+        let mut settings = BotSettings::new(name, String::from("1:abc"));
+        settings.source = UpdateSource::Webhook {
+            url: url.to_string(),
+            secret_token: None,
+        };
+        settings
+    }
+
+    #[test]
+    fn rejects_webhooks_sharing_a_path() {
+        let mut config = SupervisorConfig::single(webhook("shop", "https://a.example/hook"));
+        config
+            .bots
+            .push(webhook("office", "https://b.example/hook?bot=2"));
+        assert!(config.validate().is_err());
+        config.bots[1] = webhook("office", "https://a.example/office");
+        assert!(config.validate().is_ok());
+    }
+}
@@ -0,0 +1,226 @@
+use crate::api_client::ApiError;
+use std::collections::hash_map::RandomState;
+use std::fmt::Display;
+use std::hash::{BuildHasher, Hasher};
+use std::time::{Duration, SystemTime};
+use tokio::sync::RwLock;
+
+/// Delay after the first failure, doubled on each further one.
+const BACKOFF_BASE: Duration = Duration::from_secs(1);
+/// Upper bound of the delay between two attempts.
+const BACKOFF_MAX: Duration = Duration::from_secs(60);
+/// How long to wait before polling again after a 409 conflict.
+pub const CONFLICT_WAIT: Duration = Duration::from_secs(30);
+
+/// Exponential backoff with equal jitter: each delay lies between half the
+/// current ceiling and the ceiling, so retries never come right away.
+pub struct Backoff {
+    failures: u32,
+    random: RandomState,
+}
+
+impl Backoff {
+    pub fn new() -> Self {
+        Backoff {
+            failures: 0,
+            random: RandomState::new(),
+        }
+    }
+
+    pub fn failures(&self) -> u32 {
+        self.failures
+    }
+
+    /// Records a failure and returns how long to wait before retrying.
+    pub fn next_delay(&mut self) -> Duration {
+        let ceiling = BACKOFF_BASE
+            .saturating_mul(2u32.saturating_pow(self.failures))
+            .min(BACKOFF_MAX);
+        self.failures = self.failures.saturating_add(1);
+        // Spread retries of several bots over the window, so they don't
+        // hit the API at the same moment after an outage.
+        let mut hasher = self.random.build_hasher();
+        hasher.write_u32(self.failures);
+        if let Ok(now) = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH) {
+            hasher.write_u128(now.as_nanos());
+        }
+        let millis = ceiling.as_millis() as u64;
+        Duration::from_millis(millis / 2 + hasher.finish() % (millis / 2 + 1))
+    }
+
+    pub fn reset(&mut self) {
+        self.failures = 0;
+    }
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// State of a polling update source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PollingHealth {
+    /// The source hasn't received a response yet
+    Starting,
+    /// The last poll succeeded
+    Healthy,
+    /// Polling failed and is retried after a delay
+    Backoff {
+        failures: u32,
+        retry_in: Duration,
+        last_error: String,
+    },
+    /// Another instance polls with the same token
+    Conflict(String),
+    /// The source gave up, e.g. because the token was revoked
+    Stopped(String),
+}
+
+impl PollingHealth {
+    /// Name of the state without details, e.g. `backoff`.
+    pub fn state(&self) -> &'static str {
+        match self {
+            PollingHealth::Starting => "starting",
+            PollingHealth::Healthy => "healthy",
+            PollingHealth::Backoff { .. } => "backoff",
+            PollingHealth::Conflict(_) => "conflict",
+            PollingHealth::Stopped(_) => "stopped",
+        }
+    }
+}
+
+impl Display for PollingHealth {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PollingHealth::Starting => write!(f, "starting"),
+            PollingHealth::Healthy => write!(f, "healthy"),
+            PollingHealth::Backoff {
+                failures,
+                retry_in,
+                last_error,
+            } => write!(
+                f,
+                "backing off after {failures} failure(s), retry in {:.1}s: {last_error}",
+                retry_in.as_secs_f64()
+            ),
+            PollingHealth::Conflict(description) => write!(f, "conflict: {description}"),
+            PollingHealth::Stopped(reason) => write!(f, "stopped: {reason}"),
+        }
+    }
+}
+
+/// Health of an update source, shared between the source and observers.
+pub struct SourceHealth {
+    state: RwLock<PollingHealth>,
+}
+
+impl SourceHealth {
+    pub fn new() -> Self {
+        SourceHealth {
+            state: RwLock::new(PollingHealth::Starting),
+        }
+    }
+
+    pub async fn get(&self) -> PollingHealth {
+        self.state.read().await.clone()
+    }
+
+    pub async fn set(&self, health: PollingHealth) {
+        *self.state.write().await = health;
+    }
+}
+
+impl Default for SourceHealth {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// What the polling loop should do after a failed `getUpdates`.
+pub enum Recovery {
+    /// Wait for the given time and poll again
+    Retry(Duration),
+    /// Give up polling
+    Stop(String),
+}
+
+/// Decides how to recover from a polling error and updates the health.
+pub async fn recover(error: &ApiError, backoff: &mut Backoff, health: &SourceHealth) -> Recovery {
+    match error {
+        ApiError::Unauthorized(description) => {
+            let reason =
+                format!("the token was rejected ({description}), check the token with @BotFather");
+            health.set(PollingHealth::Stopped(reason.clone())).await;
+            Recovery::Stop(reason)
+        }
+        ApiError::Conflict(description) => {
+            health
+                .set(PollingHealth::Conflict(description.clone()))
+                .await;
+            Recovery::Retry(CONFLICT_WAIT)
+        }
+        ApiError::RetryAfter(seconds) => {
+            let retry_in = Duration::from_secs(*seconds);
+            health
+                .set(PollingHealth::Backoff {
+                    failures: backoff.failures(),
+                    retry_in,
+                    last_error: error.to_string(),
+                })
+                .await;
+            Recovery::Retry(retry_in)
+        }
+        _ => {
+            let retry_in = backoff.next_delay();
+            health
+                .set(PollingHealth::Backoff {
+                    failures: backoff.failures(),
+                    retry_in,
+                    last_error: error.to_string(),
+                })
+                .await;
+            Recovery::Retry(retry_in)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Backoff, BACKOFF_MAX};
+    use std::time::Duration;
+
+    #[test]
+    fn delays_grow_up_to_the_ceiling() {
+        let mut backoff = Backoff::new();
+        for ceiling in [1, 2, 4, 8, 16, 32, 60, 60, 60] {
+            let ceiling = Duration::from_secs(ceiling);
+            let delay = backoff.next_delay();
+            assert!(
+                delay >= ceiling / 2 && delay <= ceiling,
+                "{delay:?} for {ceiling:?}"
+            );
+        }
+        assert_eq!(backoff.failures(), 9);
+    }
+
+    #[test]
+    fn stays_capped_after_many_failures() {
+        let mut backoff = Backoff::new();
+        for _ in 0..100 {
+            assert!(backoff.next_delay() <= BACKOFF_MAX);
+        }
+    }
+
+    #[test]
+    fn reset_starts_over() {
+        let mut backoff = Backoff::new();
+        for _ in 0..5 {
+            backoff.next_delay();
+        }
+        backoff.reset();
+        assert_eq!(backoff.failures(), 0);
+        assert!(backoff.next_delay() <= Duration::from_secs(1));
+    }
+}
@@ -0,0 +1,415 @@
+use crate::api_client::{ApiClient, ApiError};
+use crate::core::{panic_message, BotCommand, CommandRegistry};
+use crate::core::{route_update, Acl, AclConfig, Bot, CommandHandler, Incoming, Registry, Token};
+use crate::http_client::HttpClient;
+use crate::i18n::I18n;
+use crate::metrics::Metrics;
+use crate::storage::{ScopedStorage, Storage};
+use crate::supervisor::config::{BotSettings, SupervisorConfig, UpdateSource};
+use crate::supervisor::polling::{recover, Backoff, PollingHealth, Recovery, SourceHealth};
+use crate::supervisor::webhook::{self, path_of, WebhookTarget};
+use crate::supervisor::Delivery;
+use crate::types;
+use futures_util::FutureExt;
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::panic::AssertUnwindSafe;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, Mutex};
+
+/// Updates waiting for a worker, shared by all bots.
+const QUEUE_SIZE: usize = 100;
+
+/// Services every bot is built with.
+pub struct Services {
+    pub metrics: Option<Arc<Metrics>>,
+    pub storage: Arc<dyn Storage>,
+    pub i18n: Arc<I18n>,
+    pub acl: AclConfig,
+    /// Keep the state of each bot under its name, so bots sharing a
+    /// storage backend don't see each other's data.
+    pub scope_storage: bool,
+}
+
+/// A bot run by the supervisor, with its own API client and handlers.
+pub struct BotInstance<T>
+where
+    T: HttpClient,
+{
+    pub bot: Arc<Bot>,
+    pub api_client: Arc<ApiClient<T>>,
+    pub registry: Arc<Registry>,
+    pub acl: Arc<Acl>,
+    /// State of the polling source; stays `Starting` for webhook bots.
+    pub health: Arc<SourceHealth>,
+    source: UpdateSource,
+    allowed_updates: Option<Vec<String>>,
+    limit: Option<u32>,
+}
+
+impl<T> BotInstance<T>
+where
+    T: HttpClient,
+{
+    /// Update types to ask Telegram for: the configured ones, or those the
+    /// registered handlers need. Empty means Telegram's default set.
+    pub async fn allowed_updates(&self) -> Vec<String> {
+        allowed_updates(&self.bot, &self.allowed_updates).await
+    }
+}
+
+/// API clients of all bots by name. Lets a handler registered for several
+/// bots answer through the bot the update came from.
+pub struct BotClients<T>
+where
+    T: HttpClient,
+{
+    clients: HashMap<String, Arc<ApiClient<T>>>,
+}
+
+impl<T> BotClients<T>
+where
+    T: HttpClient,
+{
+    pub fn get(&self, ctx: &Bot) -> Option<&Arc<ApiClient<T>>> {
+        self.clients.get(&ctx.name)
+    }
+}
+
+/// Runs several bots in one process. All bots share one HTTP client and
+/// one pool of workers handling their updates.
+pub struct Supervisor<T>
+where
+    T: HttpClient,
+{
+    client: Arc<T>,
+    bots: Vec<BotInstance<T>>,
+    workers: usize,
+    webhook_addr: Option<SocketAddr>,
+    handler_timeout: Duration,
+}
+
+fn print_me(name: &str, resp: Option<types::Bot>) {
+    if let Some(resp) = resp {
+        println!("Bot {name}: OK");
+        println!(
+            "My ID: {}\nMy name is: {} ({})",
+            resp.id, resp.first_name, resp.username
+        );
+        if resp.is_bot {
+            println!("I am a bot!");
+        } else {
+            println!("I am not a bot!");
+        }
+    } else {
+        println!("Bot {name}: ERROR");
+    }
+}
+
+async fn allowed_updates(bot: &Bot, configured: &Option<Vec<String>>) -> Vec<String> {
+    match configured {
+        Some(allowed_updates) => allowed_updates.clone(),
+        None => bot.handlers.allowed_updates().await,
+    }
+}
+
+async fn updates_loop<T>(instance: BotInstance<T>, tx: mpsc::Sender<Delivery>)
+where
+    T: HttpClient,
+{
+    let BotInstance {
+        bot,
+        api_client,
+        health,
+        limit,
+        ..
+    } = &instance;
+    print_me(&bot.name, api_client.get_me().await);
+    api_client.delete_webhook(false).await;
+    let mut backoff = Backoff::new();
+    let mut requested = None;
+    loop {
+        // Handlers may be registered while polling, follow them.
+        let allowed_updates = instance.allowed_updates().await;
+        if requested.as_ref() != Some(&allowed_updates) {
+            println!("Bot {}: allowed updates {:?}", bot.name, allowed_updates);
+            requested = Some(allowed_updates.clone());
+        }
+        // Always sent: when omitted, Telegram keeps the list of the last call.
+        let updates = match api_client.get_updates(*limit, Some(allowed_updates)).await {
+            Ok(updates) => updates,
+            Err(err) => match recover(&err, &mut backoff, health).await {
+                Recovery::Retry(delay) => {
+                    println!("Bot {}: polling {}", bot.name, health.get().await);
+                    if let ApiError::Conflict(_) = err {
+                        println!(
+                            "Bot {}: another instance is polling with this token, waiting",
+                            bot.name
+                        );
+                    }
+                    tokio::time::sleep(delay).await;
+                    continue;
+                }
+                Recovery::Stop(reason) => {
+                    println!("Bot {}: polling stopped: {reason}", bot.name);
+                    return;
+                }
+            },
+        };
+        backoff.reset();
+        health.set(PollingHealth::Healthy).await;
+        for update in updates {
+            api_client.update_offset(update.update_id);
+            println!(
+                "Bot {} update ID: {}\n{}",
+                bot.name,
+                update.get_update_id(),
+                match (update.edited_message.as_ref(), update.message.as_ref()) {
+                    (Some(edited_message), _) => format!("Edited message: {}", edited_message),
+                    (_, Some(message)) => format!("New message: {}", message),
+                    _ => "No message".to_string(),
+                }
+            );
+            match route_guarded(&bot.name, update) {
+                Some(incoming) => {
+                    if tx.send((bot.clone(), incoming)).await.is_err() {
+                        println!("Bot {}: workers stopped, polling stopped", bot.name);
+                        return;
+                    }
+                }
+                None => {
+                    println!("No command");
+                }
+            };
+        }
+    }
+}
+
+/// Routes an update like `route_update`, logging and dropping it instead
+/// of taking the update source down if routing panics.
+pub(crate) fn route_guarded(bot: &str, update: types::Update) -> Option<Incoming> {
+    route_with(bot, update, route_update)
+}
+
+fn route_with<F>(bot: &str, update: types::Update, route: F) -> Option<Incoming>
+where
+    F: FnOnce(types::Update) -> Option<Incoming>,
+{
+    let update_id = update.update_id;
+    match std::panic::catch_unwind(AssertUnwindSafe(|| route(update))) {
+        Ok(incoming) => incoming,
+        Err(payload) => {
+            println!(
+                "Bot {bot}: dropping update {update_id}, routing panicked: {}",
+                panic_message(payload.as_ref())
+            );
+            None
+        }
+    }
+}
+
+async fn worker(rx: Arc<Mutex<mpsc::Receiver<Delivery>>>) {
+    loop {
+        let Some((bot, incoming)) = rx.lock().await.recv().await else {
+            return;
+        };
+        let handlers = bot.handlers.clone();
+        let update = incoming.summary();
+        let dispatch = async move {
+            match incoming {
+                Incoming::Command(command) => handlers.dispatch(bot, *command).await,
+                Incoming::Event(event) => handlers.dispatch_event(bot, *event).await,
+                Incoming::Text(message) => handlers.dispatch_text(bot, *message).await,
+                Incoming::EditedCommand(command) => handlers.dispatch_edited(bot, *command).await,
+            }
+        };
+        // Handlers are guarded by the registry; this keeps the worker alive
+        // if anything else panics, e.g. a denial handler.
+        if let Err(payload) = AssertUnwindSafe(dispatch).catch_unwind().await {
+            println!(
+                "Dispatch panicked handling {update}: {}",
+                panic_message(payload.as_ref())
+            );
+        }
+    }
+}
+
+impl<T> Supervisor<T>
+where
+    T: HttpClient + Send + Sync + 'static,
+{
+    pub fn new(client: Arc<T>, config: &SupervisorConfig) -> Self {
+        Self {
+            client,
+            bots: Vec::new(),
+            workers: config.workers.max(1),
+            webhook_addr: config.webhook_addr,
+            handler_timeout: Duration::from_secs(config.handler_timeout),
+        }
+    }
+
+    pub async fn add_bot(&mut self, settings: BotSettings, services: &Services) -> &BotInstance<T> {
+        let client = self.client.clone();
+        self.add_bot_with_client(settings, services, client).await
+    }
+
+    /// Adds a bot talking to the API through its own HTTP client, e.g. one
+    /// using a different proxy.
+    pub async fn add_bot_with_client(
+        &mut self,
+        settings: BotSettings,
+        services: &Services,
+        client: Arc<T>,
+    ) -> &BotInstance<T> {
+        let storage: Arc<dyn Storage> = if services.scope_storage {
+            Arc::new(ScopedStorage::new(services.storage.clone(), &settings.name))
+        } else {
+            services.storage.clone()
+        };
+        let acl = Acl::new(services.acl.clone());
+        acl.load(storage.clone()).await;
+        let registry = Registry::with_acl(acl.clone());
+        registry.set_handler_timeout(self.handler_timeout).await;
+        let bot = Arc::new(Bot::new(
+            settings.name,
+            Token::new(settings.token),
+            0.into(),
+            settings.polling_timeout,
+            settings.base_url,
+            settings.local_mode,
+            registry.clone(),
+            services.metrics.clone(),
+            storage.clone(),
+            services.i18n.with_storage(storage),
+        ));
+        let api_client = Arc::new(ApiClient::new(client, bot.clone()));
+        acl.set_admin_source(api_client.clone()).await;
+        self.bots.push(BotInstance {
+            bot,
+            api_client,
+            registry,
+            acl,
+            health: Arc::new(SourceHealth::new()),
+            source: settings.source,
+            allowed_updates: settings.allowed_updates,
+            limit: settings.limit,
+        });
+        self.bots.last().unwrap()
+    }
+
+    pub fn bots(&self) -> &[BotInstance<T>] {
+        &self.bots
+    }
+
+    pub fn bot(&self, name: &str) -> Option<&BotInstance<T>> {
+        self.bots.iter().find(|instance| instance.bot.name == name)
+    }
+
+    /// Health of the polling sources by bot name. `run` consumes the
+    /// supervisor, so take it before to watch the sources while they run.
+    pub fn health(&self) -> HashMap<String, Arc<SourceHealth>> {
+        self.bots
+            .iter()
+            .filter(|instance| matches!(instance.source, UpdateSource::Polling))
+            .map(|instance| (instance.bot.name.clone(), instance.health.clone()))
+            .collect()
+    }
+
+    pub fn clients(&self) -> Arc<BotClients<T>> {
+        let clients = self
+            .bots
+            .iter()
+            .map(|instance| (instance.bot.name.clone(), instance.api_client.clone()))
+            .collect();
+        Arc::new(BotClients { clients })
+    }
+
+    /// Registers one handler for the named bots, or for all bots if `bots`
+    /// is empty. The handler sees the bot in `ctx.name`.
+    pub async fn register(
+        &self,
+        command: BotCommand,
+        bots: &[&str],
+        handler: Arc<dyn CommandHandler>,
+    ) {
+        for instance in &self.bots {
+            if bots.is_empty() || bots.contains(&instance.bot.name.as_str()) {
+                instance.registry.register(command, handler.clone()).await;
+            }
+        }
+    }
+
+    /// Starts the update sources of all bots and the workers.
+    pub async fn run(self) {
+        let (tx, rx) = mpsc::channel::<Delivery>(QUEUE_SIZE);
+        let rx = Arc::new(Mutex::new(rx));
+        for _ in 0..self.workers {
+            tokio::spawn(worker(rx.clone()));
+        }
+        let mut webhooks = HashMap::new();
+        for instance in self.bots {
+            match &instance.source {
+                UpdateSource::Polling => {
+                    tokio::spawn(updates_loop(instance, tx.clone()));
+                }
+                UpdateSource::Webhook { url, secret_token } => {
+                    let path = path_of(url);
+                    if webhooks.contains_key(&path) {
+                        println!(
+                            "Error: bot {} uses the webhook path {path} of another bot, not starting it",
+                            instance.bot.name
+                        );
+                        continue;
+                    }
+                    print_me(&instance.bot.name, instance.api_client.get_me().await);
+                    instance
+                        .api_client
+                        .set_webhook(
+                            url,
+                            secret_token.as_deref(),
+                            Some(instance.allowed_updates().await),
+                        )
+                        .await;
+                    webhooks.insert(
+                        path,
+                        WebhookTarget {
+                            bot: instance.bot.clone(),
+                            secret_token: secret_token.clone(),
+                        },
+                    );
+                }
+            }
+        }
+        if webhooks.is_empty() {
+            return;
+        }
+        match self.webhook_addr {
+            Some(addr) => {
+                tokio::spawn(webhook::serve(addr, webhooks, tx));
+            }
+            None => println!("Error: webhook bots are configured, but webhook_addr is not set"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{route_guarded, route_with};
+    use crate::core::Incoming;
+    use crate::types::fixtures::message;
+
+    #[test]
+    fn drops_updates_that_routing_panics_on() {
+        let update = || {
+            message("/echo hi")
+                .entity("bot_command", "/echo")
+                .update("message")
+        };
+        assert!(route_with("shop", update(), |_| panic!("bad update")).is_none());
+        assert!(matches!(
+            route_guarded("shop", update()),
+            Some(Incoming::Command(_))
+        ));
+    }
+}
@@ -0,0 +1,285 @@
+use crate::core::Bot;
+use crate::supervisor::runner::route_guarded;
+use crate::supervisor::Delivery;
+use crate::types::Update;
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc;
+
+/// Largest request body accepted, Telegram updates are far smaller.
+const MAX_BODY: usize = 1024 * 1024;
+
+/// Time a client has to send the whole request, so stalled connections
+/// don't keep their task alive.
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+const SECRET_HEADER: &str = "x-telegram-bot-api-secret-token";
+
+/// Bot behind a webhook path.
+pub struct WebhookTarget {
+    pub bot: Arc<Bot>,
+    pub secret_token: Option<String>,
+}
+
+/// Path part of a webhook URL, e.g. `/shop` for `https://example.com/shop?x=1`.
+pub fn path_of(url: &str) -> String {
+    let without_scheme = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let path = without_scheme
+        .find('/')
+        .map_or("/", |start| &without_scheme[start..]);
+    path.split(['?', '#']).next().unwrap_or("/").to_string()
+}
+
+/// Compares the secret token without stopping at the first difference, so
+/// response times don't reveal how much of a guess was right.
+fn secret_matches(expected: Option<&str>, given: Option<&String>) -> bool {
+    let (expected, given) = match (expected, given) {
+        (None, None) => return true,
+        (Some(expected), Some(given)) => (expected.as_bytes(), given.as_bytes()),
+        _ => return false,
+    };
+    let mut diff = expected.len() ^ given.len();
+    for (i, byte) in expected.iter().enumerate() {
+        diff |= usize::from(byte ^ given.get(i).copied().unwrap_or(0));
+    }
+    diff == 0
+}
+
+struct Request {
+    method: String,
+    path: String,
+    headers: HashMap<String, String>,
+    body: Vec<u8>,
+}
+
+async fn read_request(stream: &mut TcpStream) -> Option<Request> {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 4096];
+    let header_end = loop {
+        let read = stream.read(&mut chunk).await.ok()?;
+        if read == 0 {
+            return None;
+        }
+        buf.extend_from_slice(&chunk[..read]);
+        if let Some(end) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+            break end + 4;
+        }
+        if buf.len() > MAX_BODY {
+            return None;
+        }
+    };
+    let head = String::from_utf8_lossy(&buf[..header_end]).to_string();
+    let mut lines = head.lines();
+    let mut request_line = lines.next()?.split_whitespace();
+    let method = request_line.next()?.to_string();
+    // Targets are looked up without the query, as registered by `path_of`.
+    let path = path_of(request_line.next()?);
+    let headers: HashMap<String, String> = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_lowercase(), value.trim().to_string()))
+        .collect();
+    let length = headers
+        .get("content-length")
+        .and_then(|length| length.parse::<usize>().ok())
+        .unwrap_or(0);
+    if length > MAX_BODY {
+        return None;
+    }
+    let mut body = buf[header_end..].to_vec();
+    while body.len() < length {
+        let read = stream.read(&mut chunk).await.ok()?;
+        if read == 0 {
+            return None;
+        }
+        body.extend_from_slice(&chunk[..read]);
+    }
+    body.truncate(length);
+    Some(Request {
+        method,
+        path,
+        headers,
+        body,
+    })
+}
+
+async fn respond(stream: &mut TcpStream, status: &str) {
+    let response = format!("HTTP/1.1 {status}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n");
+    let _ = stream.write_all(response.as_bytes()).await;
+    let _ = stream.shutdown().await;
+}
+
+async fn handle_connection(
+    mut stream: TcpStream,
+    targets: Arc<HashMap<String, WebhookTarget>>,
+    tx: mpsc::Sender<Delivery>,
+) {
+    let request = match tokio::time::timeout(READ_TIMEOUT, read_request(&mut stream)).await {
+        Ok(Some(request)) => request,
+        Ok(None) => {
+            respond(&mut stream, "400 Bad Request").await;
+            return;
+        }
+        Err(_) => {
+            respond(&mut stream, "408 Request Timeout").await;
+            return;
+        }
+    };
+    let Some(target) = targets.get(&request.path) else {
+        respond(&mut stream, "404 Not Found").await;
+        return;
+    };
+    if request.method != "POST" {
+        respond(&mut stream, "405 Method Not Allowed").await;
+        return;
+    }
+    if !secret_matches(
+        target.secret_token.as_deref(),
+        request.headers.get(SECRET_HEADER),
+    ) {
+        println!("Webhook of bot {}: wrong secret token", target.bot.name);
+        respond(&mut stream, "403 Forbidden").await;
+        return;
+    }
+    let update: Update = match serde_json::from_slice(&request.body) {
+        Ok(update) => update,
+        Err(err) => {
+            println!("Webhook of bot {}: bad update: {err}", target.bot.name);
+            respond(&mut stream, "400 Bad Request").await;
+            return;
+        }
+    };
+    if let Some(metrics) = &target.bot.metrics {
+        metrics.record_update(&target.bot.name, update.kind());
+    }
+    // Confirm only queued updates, Telegram sends the others again.
+    // Updates that can't be routed are confirmed, or they would come back.
+    if let Some(incoming) = route_guarded(&target.bot.name, update) {
+        if tx.send((target.bot.clone(), incoming)).await.is_err() {
+            println!("Webhook of bot {}: workers stopped", target.bot.name);
+            respond(&mut stream, "503 Service Unavailable").await;
+            return;
+        }
+    }
+    respond(&mut stream, "200 OK").await;
+}
+
+/// Receives updates of webhook bots and queues them for the workers.
+pub async fn serve(
+    addr: SocketAddr,
+    targets: HashMap<String, WebhookTarget>,
+    tx: mpsc::Sender<Delivery>,
+) {
+    let listener = match TcpListener::bind(addr).await {
+        Ok(listener) => listener,
+        Err(err) => {
+            println!("Failed to bind webhook server on {addr}: {err}");
+            return;
+        }
+    };
+    println!("Serving webhooks on {addr}");
+    let targets = Arc::new(targets);
+    loop {
+        let (stream, _) = match listener.accept().await {
+            Ok(conn) => conn,
+            Err(err) => {
+                println!("Webhook server accept error: {err}");
+                continue;
+            }
+        };
+        tokio::spawn(handle_connection(stream, targets.clone(), tx.clone()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{handle_connection, path_of, WebhookTarget};
+    use crate::supervisor::Delivery;
+    use crate::types::fixtures::{bot, message};
+    use std::collections::HashMap;
+    use std::sync::Arc;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::{TcpListener, TcpStream};
+    use tokio::sync::mpsc;
+
+    #[test]
+    fn strips_query_and_fragment() {
+        assert_eq!(path_of("https://example.com/shop?x=1"), "/shop");
+        assert_eq!(path_of("/shop?x=1"), "/shop");
+        assert_eq!(path_of("/shop#top"), "/shop");
+        assert_eq!(path_of("https://example.com"), "/");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn times_out_stalled_clients() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let mut client = TcpStream::connect(listener.local_addr().unwrap())
+            .await
+            .unwrap();
+        let (stream, _) = listener.accept().await.unwrap();
+        let (tx, _rx) = mpsc::channel(1);
+        let server = tokio::spawn(handle_connection(stream, Arc::new(HashMap::new()), tx));
+        client.write_all(b"POST /shop HTTP/1.1\r\n").await.unwrap();
+        server.await.unwrap();
+        let mut response = String::new();
+        client.read_to_string(&mut response).await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 408"));
+    }
+
+    /// Posts `body` to a server holding the `shop` bot, returns the status line.
+    async fn post(secret: Option<&str>, body: &str, tx: mpsc::Sender<Delivery>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let mut client = TcpStream::connect(listener.local_addr().unwrap())
+            .await
+            .unwrap();
+        let (stream, _) = listener.accept().await.unwrap();
+        let target = WebhookTarget {
+            bot: bot("shop"),
+            secret_token: Some(String::from("s3cret")),
+        };
+        let targets = Arc::new(HashMap::from([(String::from("/shop"), target)]));
+        let server = tokio::spawn(handle_connection(stream, targets, tx));
+        let secret = secret
+            .map(|secret| format!("X-Telegram-Bot-Api-Secret-Token: {secret}\r\n"))
+            .unwrap_or_default();
+        let request = format!(
+            "POST /shop HTTP/1.1\r\n{secret}Content-Length: {}\r\n\r\n{body}",
+            body.len()
+        );
+        client.write_all(request.as_bytes()).await.unwrap();
+        server.await.unwrap();
+        let mut response = String::new();
+        client.read_to_string(&mut response).await.unwrap();
+        response.lines().next().unwrap_or_default().to_string()
+    }
+
+    fn command_update() -> String {
+        let update = message("/echo hi")
+            .entity("bot_command", "/echo")
+            .update("message");
+        serde_json::to_string(&update).unwrap()
+    }
+
+    #[tokio::test]
+    async fn rejects_wrong_secrets() {
+        let (tx, _rx) = mpsc::channel(1);
+        for secret in [None, Some(""), Some("s3cre"), Some("s3cret!")] {
+            let status = post(secret, &command_update(), tx.clone()).await;
+            assert_eq!(status, "HTTP/1.1 403 Forbidden", "{secret:?}");
+        }
+    }
+
+    #[tokio::test]
+    async fn confirms_only_queued_updates() {
+        let (tx, mut rx) = mpsc::channel(1);
+        let status = post(Some("s3cret"), &command_update(), tx.clone()).await;
+        assert_eq!(status, "HTTP/1.1 200 OK");
+        assert_eq!(rx.recv().await.unwrap().0.name, "shop");
+        drop(rx);
+        let status = post(Some("s3cret"), &command_update(), tx).await;
+        assert_eq!(status, "HTTP/1.1 503 Service Unavailable");
+    }
+}
@@ -1,14 +1,48 @@
-pub use crate::types::bot::{Bot, GetMeResponse};
-pub use crate::types::chat::Chat;
+pub use crate::types::bot::{Bot, CommandDescription, GetMeResponse};
+pub use crate::types::business::{BusinessBotRights, BusinessConnection, BusinessMessagesDeleted};
+pub use crate::types::chat::{Chat, ChatFullInfo, ChatType};
+pub use crate::types::chat_member::{
+    ChatJoinRequest, ChatMember, ChatMemberStatus, ChatMemberUpdated,
+};
+pub use crate::types::chat_permissions::{ChatAdministratorRights, ChatPermissions};
 pub use crate::types::entities::{Entity, EntityType};
-pub use crate::types::message::Message;
+pub use crate::types::file::File;
+pub use crate::types::forum::{
+    ForumTopic, ForumTopicClosed, ForumTopicCreated, ForumTopicEdited, ForumTopicEvent,
+    ForumTopicReopened, GeneralForumTopicHidden, GeneralForumTopicUnhidden,
+};
+pub use crate::types::keyboard::{
+    ChatShared, Contact, ForceReply, KeyboardButton, KeyboardButtonPollType,
+    KeyboardButtonRequestChat, KeyboardButtonRequestUsers, Location, ReplyKeyboardMarkup,
+    ReplyKeyboardRemove, ReplyMarkup, SharedUser, UsersShared, WebAppData, WebAppInfo,
+};
+pub use crate::types::media::{
+    Animation, Audio, Document, Media, PhotoSize, Sticker, Video, VideoNote, Voice,
+};
+pub use crate::types::message::{Message, MessageId};
+pub use crate::types::payments::{
+    Invoice, LabeledPrice, OrderInfo, PreCheckoutQuery, RefundedPayment, ShippingAddress,
+    ShippingOption, ShippingQuery, StarTransaction, StarTransactions, SuccessfulPayment, STARS,
+};
+pub use crate::types::poll::{Poll, PollAnswer, PollOption, PollType};
 pub use crate::types::user::{User, UserOption};
 use serde::{Deserialize, Serialize};
 
 pub mod bot;
+pub mod business;
 pub mod chat;
+pub mod chat_member;
+pub mod chat_permissions;
 pub mod entities;
+pub mod file;
+#[cfg(test)]
+pub mod fixtures;
+pub mod forum;
+pub mod keyboard;
+pub mod media;
 pub mod message;
+pub mod payments;
+pub mod poll;
 pub mod user;
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -20,23 +54,34 @@ pub struct Update {
     pub edited_message: Option<Message>,
     pub channel_post: Option<Message>,
     pub edited_channel_post: Option<Message>,
-    business_connection: Option<serde_json::Value>,
-    business_message: Option<serde_json::Value>,
-    edited_business_message: Option<serde_json::Value>,
-    deleted_business_message: Option<serde_json::Value>,
+    /// Optional. The bot was connected to or disconnected from a business account, or a user edited an existing connection with the bot
+    pub business_connection: Option<BusinessConnection>,
+    /// Optional. New message from a connected business account
+    pub business_message: Option<Message>,
+    /// Optional. New version of a message from a connected business account
+    pub edited_business_message: Option<Message>,
+    /// Optional. Messages were deleted from a connected business account
+    pub deleted_business_messages: Option<BusinessMessagesDeleted>,
     message_reaction: Option<serde_json::Value>,
     message_reaction_cound: Option<serde_json::Value>,
     inline_query: Option<serde_json::Value>,
     chosen_inline_result: Option<serde_json::Value>,
     callback_query: Option<serde_json::Value>,
-    shipping_query: Option<serde_json::Value>,
-    pre_checkout_query: Option<serde_json::Value>,
+    /// Optional. New incoming shipping query. Only for invoices with flexible price
+    pub shipping_query: Option<ShippingQuery>,
+    /// Optional. New incoming pre-checkout query. Contains full information about checkout
+    pub pre_checkout_query: Option<PreCheckoutQuery>,
     purchased_paid_media: Option<serde_json::Value>,
-    poll: Option<serde_json::Value>,
-    poll_answer: Option<serde_json::Value>,
-    my_chat_member: Option<serde_json::Value>,
-    chat_member: Option<serde_json::Value>,
-    chat_join_request: Option<serde_json::Value>,
+    /// Optional. New poll state. Bots receive only updates about manually stopped polls and polls, which are sent by the bot
+    pub poll: Option<Poll>,
+    /// Optional. A user changed their answer in a non-anonymous poll. Bots receive new votes only in polls that were sent by the bot itself.
+    pub poll_answer: Option<PollAnswer>,
+    /// Optional. The bot's chat member status was updated in a chat.
+    pub my_chat_member: Option<ChatMemberUpdated>,
+    /// Optional. A chat member's status was updated in a chat. The bot must be an administrator in the chat and must explicitly specify “chat_member” in the list of allowed_updates to receive these updates.
+    pub chat_member: Option<ChatMemberUpdated>,
+    /// Optional. A request to join the chat has been sent.
+    pub chat_join_request: Option<ChatJoinRequest>,
     chat_boost: Option<serde_json::Value>,
     removed_chat_boost: Option<serde_json::Value>,
 }
@@ -45,4 +90,48 @@ impl Update {
     pub fn get_update_id(&self) -> i64 {
         self.update_id
     }
+
+    /// Returns the name of the field that carries the update payload,
+    /// e.g. `message` or `callback_query`.
+    pub fn kind(&self) -> &'static str {
+        let kinds = [
+            ("message", self.message.is_some()),
+            ("edited_message", self.edited_message.is_some()),
+            ("channel_post", self.channel_post.is_some()),
+            ("edited_channel_post", self.edited_channel_post.is_some()),
+            ("business_connection", self.business_connection.is_some()),
+            ("business_message", self.business_message.is_some()),
+            (
+                "edited_business_message",
+                self.edited_business_message.is_some(),
+            ),
+            (
+                "deleted_business_messages",
+                self.deleted_business_messages.is_some(),
+            ),
+            ("message_reaction", self.message_reaction.is_some()),
+            (
+                "message_reaction_count",
+                self.message_reaction_cound.is_some(),
+            ),
+            ("inline_query", self.inline_query.is_some()),
+            ("chosen_inline_result", self.chosen_inline_result.is_some()),
+            ("callback_query", self.callback_query.is_some()),
+            ("shipping_query", self.shipping_query.is_some()),
+            ("pre_checkout_query", self.pre_checkout_query.is_some()),
+            ("purchased_paid_media", self.purchased_paid_media.is_some()),
+            ("poll", self.poll.is_some()),
+            ("poll_answer", self.poll_answer.is_some()),
+            ("my_chat_member", self.my_chat_member.is_some()),
+            ("chat_member", self.chat_member.is_some()),
+            ("chat_join_request", self.chat_join_request.is_some()),
+            ("chat_boost", self.chat_boost.is_some()),
+            ("removed_chat_boost", self.removed_chat_boost.is_some()),
+        ];
+        kinds
+            .iter()
+            .find(|(_, present)| *present)
+            .map(|(kind, _)| *kind)
+            .unwrap_or("unknown")
+    }
 }
@@ -18,3 +18,10 @@ pub struct GetMeResponse {
     pub ok: bool,
     pub result: Bot,
 }
+
+/// A command shown in the bot menu, as sent to `setMyCommands`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommandDescription {
+    pub command: String,
+    pub description: String,
+}
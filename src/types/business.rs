@@ -0,0 +1,56 @@
+use crate::types::{Chat, User};
+use serde::{Deserialize, Serialize};
+
+/// What a bot may do on behalf of a business account.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BusinessBotRights {
+    pub can_reply: Option<bool>,
+    pub can_read_messages: Option<bool>,
+    pub can_delete_sent_messages: Option<bool>,
+    pub can_delete_all_messages: Option<bool>,
+    pub can_edit_name: Option<bool>,
+    pub can_edit_bio: Option<bool>,
+    pub can_edit_profile_photo: Option<bool>,
+    pub can_edit_username: Option<bool>,
+    pub can_change_gift_settings: Option<bool>,
+    pub can_view_gifts_and_stars: Option<bool>,
+    pub can_convert_gifts_to_stars: Option<bool>,
+    pub can_transfer_and_upgrade_gifts: Option<bool>,
+    pub can_transfer_stars: Option<bool>,
+    pub can_manage_stories: Option<bool>,
+}
+
+/// Connection of the bot with a business account.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BusinessConnection {
+    pub id: String,
+    /// Business account user that created the business connection
+    pub user: User,
+    /// Identifier of a private chat with the user who created the business connection
+    pub user_chat_id: i64,
+    pub date: i64,
+    pub rights: Option<BusinessBotRights>,
+    /// False if the connection was disabled or deleted
+    pub is_enabled: bool,
+}
+
+impl BusinessConnection {
+    /// Returns true if the bot may send messages on behalf of the account.
+    pub fn can_reply(&self) -> bool {
+        self.is_enabled
+            && self
+                .rights
+                .as_ref()
+                .and_then(|rights| rights.can_reply)
+                .unwrap_or(false)
+    }
+}
+
+/// Messages deleted from a connected business account.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BusinessMessagesDeleted {
+    pub business_connection_id: String,
+    /// Information about a chat in the business account. The bot may not have access to the chat or the corresponding user.
+    pub chat: Chat,
+    pub message_ids: Vec<i64>,
+}
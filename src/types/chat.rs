@@ -1,15 +1,49 @@
 use std::fmt::Display;
 
+use crate::types::{ChatPermissions, Message};
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Serialize, Deserialize)]
+/// Type of a chat.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChatType {
+    Private,
+    Group,
+    Supergroup,
+    Channel,
+}
+
+impl ChatType {
+    /// Returns true for groups and supergroups.
+    pub fn is_group(&self) -> bool {
+        matches!(self, ChatType::Group | ChatType::Supergroup)
+    }
+}
+
+impl Display for ChatType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            ChatType::Private => "private",
+            ChatType::Group => "group",
+            ChatType::Supergroup => "supergroup",
+            ChatType::Channel => "channel",
+        };
+        write!(f, "{name}")
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Chat {
     id: i64,
     #[serde(rename = "type")]
-    chat_type: String,
+    chat_type: ChatType,
+    /// Title, for supergroups, channels and group chats
+    title: Option<String>,
+    username: Option<String>,
     first_name: Option<String>,
     last_name: Option<String>,
-    username: Option<String>,
+    /// True, if the supergroup chat is a forum (has topics enabled)
+    is_forum: Option<bool>,
 }
 
 impl Chat {
@@ -17,25 +51,87 @@ impl Chat {
     pub fn id(&self) -> i64 {
         self.id
     }
+
+    /// Returns the type of the chat: private, group, supergroup or channel.
+    pub fn chat_type(&self) -> ChatType {
+        self.chat_type
+    }
+
+    pub fn title(&self) -> Option<&str> {
+        self.title.as_deref()
+    }
+
+    pub fn username(&self) -> Option<&str> {
+        self.username.as_deref()
+    }
+
+    pub fn first_name(&self) -> Option<&str> {
+        self.first_name.as_deref()
+    }
+
+    pub fn last_name(&self) -> Option<&str> {
+        self.last_name.as_deref()
+    }
+
+    /// Returns true if the supergroup has topics enabled.
+    pub fn is_forum(&self) -> bool {
+        self.is_forum.unwrap_or(false)
+    }
+
+    /// Title of a group or channel, or the full name of a private chat.
+    pub fn name(&self) -> String {
+        if let Some(title) = &self.title {
+            return title.clone();
+        }
+        [self.first_name.as_deref(), self.last_name.as_deref()]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
 }
 
 impl Display for Chat {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let chat_name = format!(
-            "{}{}",
-            self.first_name.clone().unwrap_or_default(),
-            self.last_name.clone().unwrap_or_default(),
-        );
-        let results = vec![
-            write!(f, "Chat("),
-            write!(f, "{chat_name}"),
-            write!(f, "(#{}, ", self.id),
-            write!(f, "{})", self.chat_type),
-            write!(f, ")"),
-        ];
-        for r in results {
-            r?
-        }
-        Ok(())
+        write!(f, "Chat({}(#{}, {}))", self.name(), self.id, self.chat_type)
     }
 }
+
+/// Full information about a chat, as returned by `getChat`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatFullInfo {
+    pub id: i64,
+    #[serde(rename = "type")]
+    pub chat_type: ChatType,
+    pub title: Option<String>,
+    pub username: Option<String>,
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+    pub is_forum: Option<bool>,
+    /// Identifier of the accent color for the chat name and backgrounds
+    pub accent_color_id: Option<i64>,
+    pub max_reaction_count: Option<i64>,
+    pub photo: Option<serde_json::Value>,
+    pub active_usernames: Option<Vec<String>>,
+    pub bio: Option<String>,
+    pub description: Option<String>,
+    pub invite_link: Option<String>,
+    /// The most recent pinned message (by sending date)
+    pub pinned_message: Option<Box<Message>>,
+    /// Default chat member permissions, for groups and supergroups
+    pub permissions: Option<ChatPermissions>,
+    /// For supergroups, the minimum allowed delay between consecutive messages sent by each unprivileged user; in seconds
+    pub slow_mode_delay: Option<i64>,
+    pub unrestrict_boost_count: Option<i64>,
+    /// The time after which all messages sent to the chat will be automatically deleted; in seconds
+    pub message_auto_delete_time: Option<i64>,
+    pub has_aggressive_anti_spam_enabled: Option<bool>,
+    pub has_hidden_members: Option<bool>,
+    pub has_protected_content: Option<bool>,
+    pub has_visible_history: Option<bool>,
+    pub sticker_set_name: Option<String>,
+    pub can_set_sticker_set: Option<bool>,
+    /// Unique identifier for the linked chat, i.e. the discussion group identifier for a channel and vice versa
+    pub linked_chat_id: Option<i64>,
+    pub location: Option<serde_json::Value>,
+}
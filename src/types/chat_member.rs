@@ -0,0 +1,106 @@
+use crate::types::{Chat, User};
+use serde::{Deserialize, Serialize};
+
+/// Status of a chat member.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChatMemberStatus {
+    /// The owner of the chat
+    Creator,
+    Administrator,
+    Member,
+    /// A member under certain restrictions, see `ChatMember::permissions`
+    Restricted,
+    Left,
+    /// Banned in the chat
+    Kicked,
+}
+
+/// Information about one member of a chat. Which of the optional fields
+/// are set depends on the status.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatMember {
+    pub status: ChatMemberStatus,
+    pub user: User,
+    /// Owner and administrators only. True, if the user's presence in the chat is hidden
+    pub is_anonymous: Option<bool>,
+    /// Owner and administrators only. Custom title for this user
+    pub custom_title: Option<String>,
+    /// Administrators only. True, if the bot is allowed to edit administrator privileges of that user
+    pub can_be_edited: Option<bool>,
+    /// Member, restricted and banned users only. Date when the restriction or ban will be lifted for this user; Unix time. 0 means forever
+    pub until_date: Option<i64>,
+    /// Restricted only. True, if the user is a member of the chat at the moment of the request
+    pub is_member: Option<bool>,
+    /// Administrator rights or restricted user permissions, depending on the status.
+    #[serde(flatten)]
+    pub rights: serde_json::Map<String, serde_json::Value>,
+}
+
+impl ChatMember {
+    /// Returns true for the chat owner and administrators.
+    pub fn is_admin(&self) -> bool {
+        matches!(
+            self.status,
+            ChatMemberStatus::Creator | ChatMemberStatus::Administrator
+        )
+    }
+
+    /// Returns true if the user is currently in the chat.
+    pub fn is_present(&self) -> bool {
+        match self.status {
+            ChatMemberStatus::Left | ChatMemberStatus::Kicked => false,
+            ChatMemberStatus::Restricted => self.is_member.unwrap_or(false),
+            _ => true,
+        }
+    }
+
+    /// Returns a `can_*` right or permission flag, false if it is not set.
+    pub fn can(&self, right: &str) -> bool {
+        self.rights
+            .get(&format!("can_{right}"))
+            .and_then(|value| value.as_bool())
+            .unwrap_or(false)
+    }
+}
+
+/// Changes in the status of a chat member.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatMemberUpdated {
+    pub chat: Chat,
+    /// Performer of the action, which resulted in the change
+    pub from: User,
+    /// Date the change was done in Unix time
+    pub date: i64,
+    pub old_chat_member: ChatMember,
+    pub new_chat_member: ChatMember,
+    /// Chat invite link, which was used by the user to join the chat; for joining by invite link events only.
+    pub invite_link: Option<serde_json::Value>,
+    pub via_join_request: Option<bool>,
+    pub via_chat_folder_invite_link: Option<bool>,
+}
+
+impl ChatMemberUpdated {
+    /// Returns true if the user was not in the chat before and is now.
+    pub fn joined(&self) -> bool {
+        !self.old_chat_member.is_present() && self.new_chat_member.is_present()
+    }
+
+    /// Returns true if the user was in the chat before and is not anymore.
+    pub fn left(&self) -> bool {
+        self.old_chat_member.is_present() && !self.new_chat_member.is_present()
+    }
+}
+
+/// A request to join the chat.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatJoinRequest {
+    pub chat: Chat,
+    /// User that sent the join request
+    pub from: User,
+    /// Identifier of a private chat with the user, the bot can use it for 5 minutes to send messages
+    pub user_chat_id: i64,
+    pub date: i64,
+    pub bio: Option<String>,
+    pub invite_link: Option<serde_json::Value>,
+}
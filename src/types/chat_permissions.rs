@@ -0,0 +1,262 @@
+use serde::{Deserialize, Serialize};
+
+/// Actions that a non-administrator user is allowed to take in a chat.
+/// Build with `ChatPermissions::none()` or `ChatPermissions::all()` and
+/// adjust single permissions with the setters:
+///
+/// ```ignore
+/// let read_only_with_polls = ChatPermissions::none().send_polls(true);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChatPermissions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub can_send_messages: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub can_send_audios: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub can_send_documents: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub can_send_photos: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub can_send_videos: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub can_send_video_notes: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub can_send_voice_notes: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub can_send_polls: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub can_send_other_messages: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub can_add_web_page_previews: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub can_change_info: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub can_invite_users: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub can_pin_messages: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub can_manage_topics: Option<bool>,
+}
+
+impl ChatPermissions {
+    fn with_all(value: bool) -> Self {
+        Self {
+            can_send_messages: Some(value),
+            can_send_audios: Some(value),
+            can_send_documents: Some(value),
+            can_send_photos: Some(value),
+            can_send_videos: Some(value),
+            can_send_video_notes: Some(value),
+            can_send_voice_notes: Some(value),
+            can_send_polls: Some(value),
+            can_send_other_messages: Some(value),
+            can_add_web_page_previews: Some(value),
+            can_change_info: Some(value),
+            can_invite_users: Some(value),
+            can_pin_messages: Some(value),
+            can_manage_topics: Some(value),
+        }
+    }
+
+    /// Nothing is allowed, e.g. to mute a user.
+    pub fn none() -> Self {
+        Self::with_all(false)
+    }
+
+    /// Everything is allowed.
+    pub fn all() -> Self {
+        Self::with_all(true)
+    }
+
+    /// Allows or forbids all kinds of messages and media.
+    pub fn send_everything(self, value: bool) -> Self {
+        self.send_messages(value)
+            .send_audios(value)
+            .send_documents(value)
+            .send_photos(value)
+            .send_videos(value)
+            .send_video_notes(value)
+            .send_voice_notes(value)
+            .send_polls(value)
+            .send_other_messages(value)
+            .add_web_page_previews(value)
+    }
+
+    pub fn send_messages(mut self, value: bool) -> Self {
+        self.can_send_messages = Some(value);
+        self
+    }
+
+    pub fn send_audios(mut self, value: bool) -> Self {
+        self.can_send_audios = Some(value);
+        self
+    }
+
+    pub fn send_documents(mut self, value: bool) -> Self {
+        self.can_send_documents = Some(value);
+        self
+    }
+
+    pub fn send_photos(mut self, value: bool) -> Self {
+        self.can_send_photos = Some(value);
+        self
+    }
+
+    pub fn send_videos(mut self, value: bool) -> Self {
+        self.can_send_videos = Some(value);
+        self
+    }
+
+    pub fn send_video_notes(mut self, value: bool) -> Self {
+        self.can_send_video_notes = Some(value);
+        self
+    }
+
+    pub fn send_voice_notes(mut self, value: bool) -> Self {
+        self.can_send_voice_notes = Some(value);
+        self
+    }
+
+    pub fn send_polls(mut self, value: bool) -> Self {
+        self.can_send_polls = Some(value);
+        self
+    }
+
+    /// Stickers, animations, games and inline bots.
+    pub fn send_other_messages(mut self, value: bool) -> Self {
+        self.can_send_other_messages = Some(value);
+        self
+    }
+
+    pub fn add_web_page_previews(mut self, value: bool) -> Self {
+        self.can_add_web_page_previews = Some(value);
+        self
+    }
+
+    pub fn change_info(mut self, value: bool) -> Self {
+        self.can_change_info = Some(value);
+        self
+    }
+
+    pub fn invite_users(mut self, value: bool) -> Self {
+        self.can_invite_users = Some(value);
+        self
+    }
+
+    pub fn pin_messages(mut self, value: bool) -> Self {
+        self.can_pin_messages = Some(value);
+        self
+    }
+
+    pub fn manage_topics(mut self, value: bool) -> Self {
+        self.can_manage_topics = Some(value);
+        self
+    }
+}
+
+/// Rights of an administrator, as used by `promoteChatMember`.
+/// Everything is off by default, enable rights with the setters.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChatAdministratorRights {
+    pub is_anonymous: bool,
+    pub can_manage_chat: bool,
+    pub can_delete_messages: bool,
+    pub can_manage_video_chats: bool,
+    pub can_restrict_members: bool,
+    pub can_promote_members: bool,
+    pub can_change_info: bool,
+    pub can_invite_users: bool,
+    pub can_post_stories: bool,
+    pub can_edit_stories: bool,
+    pub can_delete_stories: bool,
+    /// Channels only
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub can_post_messages: Option<bool>,
+    /// Channels only
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub can_edit_messages: Option<bool>,
+    /// Groups and supergroups only
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub can_pin_messages: Option<bool>,
+    /// Supergroups only
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub can_manage_topics: Option<bool>,
+}
+
+impl ChatAdministratorRights {
+    /// A moderator: can delete messages, restrict members and manage the chat.
+    pub fn moderator() -> Self {
+        Self::default()
+            .manage_chat(true)
+            .delete_messages(true)
+            .restrict_members(true)
+    }
+
+    pub fn anonymous(mut self, value: bool) -> Self {
+        self.is_anonymous = value;
+        self
+    }
+
+    pub fn manage_chat(mut self, value: bool) -> Self {
+        self.can_manage_chat = value;
+        self
+    }
+
+    pub fn delete_messages(mut self, value: bool) -> Self {
+        self.can_delete_messages = value;
+        self
+    }
+
+    pub fn manage_video_chats(mut self, value: bool) -> Self {
+        self.can_manage_video_chats = value;
+        self
+    }
+
+    pub fn restrict_members(mut self, value: bool) -> Self {
+        self.can_restrict_members = value;
+        self
+    }
+
+    pub fn promote_members(mut self, value: bool) -> Self {
+        self.can_promote_members = value;
+        self
+    }
+
+    pub fn change_info(mut self, value: bool) -> Self {
+        self.can_change_info = value;
+        self
+    }
+
+    pub fn invite_users(mut self, value: bool) -> Self {
+        self.can_invite_users = value;
+        self
+    }
+
+    pub fn manage_stories(mut self, value: bool) -> Self {
+        self.can_post_stories = value;
+        self.can_edit_stories = value;
+        self.can_delete_stories = value;
+        self
+    }
+
+    pub fn post_messages(mut self, value: bool) -> Self {
+        self.can_post_messages = Some(value);
+        self
+    }
+
+    pub fn edit_messages(mut self, value: bool) -> Self {
+        self.can_edit_messages = Some(value);
+        self
+    }
+
+    pub fn pin_messages(mut self, value: bool) -> Self {
+        self.can_pin_messages = Some(value);
+        self
+    }
+
+    pub fn manage_topics(mut self, value: bool) -> Self {
+        self.can_manage_topics = Some(value);
+        self
+    }
+}
@@ -1,5 +1,6 @@
 use serde::de;
 use std::fmt::Display;
+use std::ops::Range;
 
 use crate::types::User;
 use serde::{Deserialize, Serialize};
@@ -24,7 +25,7 @@ use serde::{Deserialize, Serialize};
 /// “text_link” (for clickable text URLs),
 /// “text_mention” (for users without usernames),
 /// “custom_emoji” (for inline custom emoji stickers)
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 pub enum EntityType {
     Mention,
     Hashtag,
@@ -92,7 +93,7 @@ impl<'de> Deserialize<'de> for EntityType {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Entity {
     #[serde(rename = "type")]
     pub entity_type: EntityType,
@@ -104,6 +105,32 @@ pub struct Entity {
     pub custom_emoji_id: Option<String>,
 }
 
+impl Entity {
+    /// Byte range of `text` the entity covers. Telegram counts `offset` and
+    /// `length` in UTF-16 code units, so they can't index the string
+    /// directly. `None` if the entity doesn't fit the text.
+    pub fn byte_range(&self, text: &str) -> Option<Range<usize>> {
+        let start = usize::try_from(self.offset).ok()?;
+        let end = start.checked_add(usize::try_from(self.length).ok()?)?;
+        let (mut start_byte, mut end_byte) = (None, None);
+        let mut units = 0;
+        let chars = text
+            .char_indices()
+            .map(|(index, ch)| (index, ch.len_utf16()));
+        for (index, len) in chars.chain(std::iter::once((text.len(), 0))) {
+            if units == start {
+                start_byte = Some(index);
+            }
+            if units == end {
+                end_byte = Some(index);
+                break;
+            }
+            units += len;
+        }
+        Some(start_byte?..end_byte?)
+    }
+}
+
 impl Display for Entity {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
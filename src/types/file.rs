@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+
+/// A file ready to be downloaded, as returned by `getFile`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct File {
+    pub file_id: String,
+    pub file_unique_id: String,
+    pub file_size: Option<i64>,
+    /// Relative path to download the file from the cloud API, or an
+    /// absolute path on disk when a local Bot API server is used.
+    pub file_path: Option<String>,
+}
@@ -0,0 +1,171 @@
+//! Builders of the updates Telegram sends, for tests.
+
+use crate::core::{Bot, Registry, Token};
+use crate::i18n::{I18n, Translations};
+use crate::storage::MemoryStorage;
+use crate::types::{Message, Poll, PollAnswer, PreCheckoutQuery, Update};
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+/// A bot with no handlers, memory storage and no translations.
+pub fn bot(name: &str) -> Arc<Bot> {
+    let storage = Arc::new(MemoryStorage::default());
+    Arc::new(Bot::new(
+        name.to_string(),
+        Token::new(String::from("1:abc")),
+        0.into(),
+        0,
+        String::from("http://api.telegram.invalid"),
+        false,
+        Registry::new(),
+        None,
+        storage.clone(),
+        I18n::new(Translations::default(), storage),
+    ))
+}
+
+/// Chat and sender of messages built by `message`, unless changed.
+pub const USER_ID: i64 = 100;
+
+/// Builds a message as Telegram would send it, by default a private text
+/// message from `USER_ID`.
+pub struct MessageBuilder {
+    json: Value,
+}
+
+pub fn message(text: &str) -> MessageBuilder {
+    MessageBuilder {
+        json: json!({
+            "message_id": 1,
+            "date": 0,
+            "chat": { "id": USER_ID, "type": "private", "first_name": "A" },
+            "from": user(USER_ID),
+            "text": text,
+        }),
+    }
+}
+
+fn user(id: i64) -> Value {
+    json!({ "id": id, "is_bot": false, "first_name": "A" })
+}
+
+impl MessageBuilder {
+    pub fn from(mut self, user_id: i64) -> Self {
+        self.json["from"] = user(user_id);
+        self
+    }
+
+    pub fn in_group(mut self, chat_id: i64) -> Self {
+        self.json["chat"] = json!({ "id": chat_id, "type": "supergroup", "title": "G" });
+        self
+    }
+
+    /// Makes it a message of the business connection `connection_id`.
+    pub fn business(mut self, connection_id: &str) -> Self {
+        self.json["business_connection_id"] = json!(connection_id);
+        self
+    }
+
+    /// Marks the message as sent by a bot on behalf of the business account.
+    pub fn sent_by_business_bot(mut self) -> Self {
+        self.json["sender_business_bot"] = json!({ "id": 42, "is_bot": true, "first_name": "Bot" });
+        self
+    }
+
+    /// Turns the text and its entities into the caption of a photo.
+    pub fn as_caption(mut self) -> Self {
+        let text = self.json["text"].take();
+        self.json["caption"] = text;
+        self.json["photo"] =
+            json!([{ "file_id": "p", "file_unique_id": "p", "width": 1, "height": 1 }]);
+        if let Some(json) = self.json.as_object_mut() {
+            json.remove("text");
+            if let Some(entities) = json.remove("entities") {
+                json.insert(String::from("caption_entities"), entities);
+            }
+        }
+        self
+    }
+
+    /// Marks the first occurrence of `part` in the text as an entity, with
+    /// the offset and length in UTF-16 code units like Telegram counts them.
+    pub fn entity(self, entity_type: &str, part: &str) -> Self {
+        let text = self.json["text"].as_str().unwrap_or_default();
+        let start = text.find(part).expect("part of the text");
+        let offset = text[..start].encode_utf16().count();
+        let length = part.encode_utf16().count();
+        self.raw_entity(entity_type, offset, length)
+    }
+
+    /// Adds an entity at any offset, even one outside the text.
+    pub fn raw_entity(mut self, entity_type: &str, offset: usize, length: usize) -> Self {
+        let entity = json!({ "type": entity_type, "offset": offset, "length": length });
+        match self.json["entities"].as_array_mut() {
+            Some(entities) => entities.push(entity),
+            None => self.json["entities"] = json!([entity]),
+        }
+        self
+    }
+
+    /// Attaches a poll, as in the message returned by `sendPoll`.
+    pub fn poll(mut self, poll: &Poll) -> Self {
+        self.json["poll"] = serde_json::to_value(poll).unwrap();
+        self
+    }
+
+    pub fn build(self) -> Message {
+        serde_json::from_value(self.json).unwrap()
+    }
+
+    /// Wraps the message in an update, `kind` being e.g. `message` or
+    /// `edited_business_message`.
+    pub fn update(self, kind: &str) -> Update {
+        update(kind, self.json)
+    }
+}
+
+/// An update carrying `payload` under `kind`.
+pub fn update(kind: &str, payload: Value) -> Update {
+    serde_json::from_value(json!({ "update_id": 1, kind: payload })).unwrap()
+}
+
+pub fn pre_checkout_query(currency: &str, total_amount: i64, payload: &str) -> PreCheckoutQuery {
+    serde_json::from_value(json!({
+        "id": "1",
+        "from": user(USER_ID),
+        "currency": currency,
+        "total_amount": total_amount,
+        "invoice_payload": payload,
+    }))
+    .unwrap()
+}
+
+/// A regular, public poll with one option per voter count.
+pub fn poll(id: &str, voter_counts: &[i64], total_voter_count: i64) -> Poll {
+    let options: Vec<Value> = voter_counts
+        .iter()
+        .enumerate()
+        .map(|(i, count)| json!({ "text": format!("Option {i}"), "voter_count": count }))
+        .collect();
+    serde_json::from_value(json!({
+        "id": id,
+        "question": "Tea or coffee?",
+        "options": options,
+        "total_voter_count": total_voter_count,
+        "is_closed": false,
+        "is_anonymous": false,
+        "type": "regular",
+        "allows_multiple_answers": false,
+    }))
+    .unwrap()
+}
+
+/// An answer of `user_id`, empty `option_ids` retract the vote.
+pub fn poll_answer(poll_id: &str, user_id: i64, option_ids: &[usize]) -> PollAnswer {
+    serde_json::from_value(json!({
+        "poll_id": poll_id,
+        "user": user(user_id),
+        "option_ids": option_ids,
+    }))
+    .unwrap()
+}
@@ -0,0 +1,57 @@
+use serde::{Deserialize, Serialize};
+
+/// A forum topic.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ForumTopic {
+    /// Unique identifier of the forum topic
+    pub message_thread_id: i64,
+    pub name: String,
+    /// Color of the topic icon in RGB format
+    pub icon_color: i64,
+    pub icon_custom_emoji_id: Option<String>,
+    pub is_name_implicit: Option<bool>,
+}
+
+/// Service message about a new forum topic created in the chat.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ForumTopicCreated {
+    pub name: String,
+    pub icon_color: i64,
+    pub icon_custom_emoji_id: Option<String>,
+    pub is_name_implicit: Option<bool>,
+}
+
+/// Service message about an edited forum topic. Only changed fields are set.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ForumTopicEdited {
+    pub name: Option<String>,
+    /// Empty string if the icon was removed
+    pub icon_custom_emoji_id: Option<String>,
+}
+
+/// Service message about a forum topic closed in the chat. Holds no information.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ForumTopicClosed {}
+
+/// Service message about a forum topic reopened in the chat. Holds no information.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ForumTopicReopened {}
+
+/// Service message about the General forum topic hidden in the chat.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GeneralForumTopicHidden {}
+
+/// Service message about the General forum topic unhidden in the chat.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GeneralForumTopicUnhidden {}
+
+/// Forum service message carried by a message.
+#[derive(Debug, Clone, Copy)]
+pub enum ForumTopicEvent<'a> {
+    Created(&'a ForumTopicCreated),
+    Edited(&'a ForumTopicEdited),
+    Closed,
+    Reopened,
+    GeneralHidden,
+    GeneralUnhidden,
+}
@@ -0,0 +1,409 @@
+use crate::types::{PhotoSize, PollType};
+use serde::{Deserialize, Serialize};
+
+/// A Web App opened by a button.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WebAppInfo {
+    /// HTTPS URL of the Web App
+    pub url: String,
+}
+
+/// Kind of poll the user is asked to create, any kind if `type` is unset.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeyboardButtonPollType {
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub poll_type: Option<PollType>,
+}
+
+/// Criteria of the users the user may share. The result arrives in
+/// `Message::users_shared` with the same `request_id`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeyboardButtonRequestUsers {
+    pub request_id: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_is_bot: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_is_premium: Option<bool>,
+    /// 1-10 users, 1 by default
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_quantity: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_name: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_username: Option<bool>,
+}
+
+impl KeyboardButtonRequestUsers {
+    pub fn new(request_id: i32) -> Self {
+        Self {
+            request_id,
+            user_is_bot: None,
+            user_is_premium: None,
+            max_quantity: None,
+            request_name: None,
+            request_username: None,
+        }
+    }
+
+    pub fn bots(mut self, user_is_bot: bool) -> Self {
+        self.user_is_bot = Some(user_is_bot);
+        self
+    }
+
+    pub fn max_quantity(mut self, max_quantity: u8) -> Self {
+        self.max_quantity = Some(max_quantity);
+        self
+    }
+
+    /// Also share the names and usernames of the users.
+    pub fn with_names(mut self) -> Self {
+        self.request_name = Some(true);
+        self.request_username = Some(true);
+        self
+    }
+}
+
+/// Criteria of the chat the user may share. The result arrives in
+/// `Message::chat_shared` with the same `request_id`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeyboardButtonRequestChat {
+    pub request_id: i32,
+    /// A channel if true, a group or supergroup otherwise
+    pub chat_is_channel: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub chat_is_forum: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub chat_is_created: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bot_is_member: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_title: Option<bool>,
+}
+
+impl KeyboardButtonRequestChat {
+    pub fn new(request_id: i32, chat_is_channel: bool) -> Self {
+        Self {
+            request_id,
+            chat_is_channel,
+            chat_is_forum: None,
+            chat_is_created: None,
+            bot_is_member: None,
+            request_title: None,
+        }
+    }
+
+    /// Only chats owned by the user.
+    pub fn created_by_user(mut self) -> Self {
+        self.chat_is_created = Some(true);
+        self
+    }
+
+    /// Only chats the bot is a member of.
+    pub fn with_bot(mut self) -> Self {
+        self.bot_is_member = Some(true);
+        self
+    }
+
+    pub fn with_title(mut self) -> Self {
+        self.request_title = Some(true);
+        self
+    }
+}
+
+/// A button of a reply keyboard. Pressing a plain button sends its text;
+/// the other kinds ask the user for something first.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeyboardButton {
+    pub text: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_users: Option<KeyboardButtonRequestUsers>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_chat: Option<KeyboardButtonRequestChat>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_contact: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_location: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_poll: Option<KeyboardButtonPollType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub web_app: Option<WebAppInfo>,
+}
+
+impl KeyboardButton {
+    /// Sends its text when pressed.
+    pub fn text(text: &str) -> Self {
+        Self {
+            text: text.to_string(),
+            request_users: None,
+            request_chat: None,
+            request_contact: None,
+            request_location: None,
+            request_poll: None,
+            web_app: None,
+        }
+    }
+
+    /// Sends the user's phone number, private chats only.
+    pub fn contact(text: &str) -> Self {
+        Self {
+            request_contact: Some(true),
+            ..Self::text(text)
+        }
+    }
+
+    /// Sends the user's current location, private chats only.
+    pub fn location(text: &str) -> Self {
+        Self {
+            request_location: Some(true),
+            ..Self::text(text)
+        }
+    }
+
+    /// Asks the user to create a poll, of any kind if `poll_type` is unset.
+    pub fn poll(text: &str, poll_type: Option<PollType>) -> Self {
+        Self {
+            request_poll: Some(KeyboardButtonPollType { poll_type }),
+            ..Self::text(text)
+        }
+    }
+
+    /// Asks the user to pick users, private chats only.
+    pub fn users(text: &str, request: KeyboardButtonRequestUsers) -> Self {
+        Self {
+            request_users: Some(request),
+            ..Self::text(text)
+        }
+    }
+
+    /// Asks the user to pick a chat, private chats only.
+    pub fn chat(text: &str, request: KeyboardButtonRequestChat) -> Self {
+        Self {
+            request_chat: Some(request),
+            ..Self::text(text)
+        }
+    }
+
+    /// Opens a Web App, which can send data back, private chats only.
+    pub fn web_app(text: &str, url: &str) -> Self {
+        Self {
+            web_app: Some(WebAppInfo {
+                url: url.to_string(),
+            }),
+            ..Self::text(text)
+        }
+    }
+}
+
+/// A custom keyboard shown instead of the system one.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReplyKeyboardMarkup {
+    pub keyboard: Vec<Vec<KeyboardButton>>,
+    /// Keep the keyboard shown when the system keyboard is hidden
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_persistent: Option<bool>,
+    /// Fit the keyboard to its buttons instead of the system keyboard height
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resize_keyboard: Option<bool>,
+    /// Hide the keyboard after a button is pressed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub one_time_keyboard: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub input_field_placeholder: Option<String>,
+    /// Show the keyboard only to mentioned users and the author of the replied message
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub selective: Option<bool>,
+}
+
+impl ReplyKeyboardMarkup {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn row(mut self, buttons: Vec<KeyboardButton>) -> Self {
+        self.keyboard.push(buttons);
+        self
+    }
+
+    /// Adds a row of plain buttons.
+    pub fn text_row(self, texts: &[&str]) -> Self {
+        self.row(
+            texts
+                .iter()
+                .map(|text| KeyboardButton::text(text))
+                .collect(),
+        )
+    }
+
+    pub fn persistent(mut self) -> Self {
+        self.is_persistent = Some(true);
+        self
+    }
+
+    pub fn resize(mut self) -> Self {
+        self.resize_keyboard = Some(true);
+        self
+    }
+
+    pub fn one_time(mut self) -> Self {
+        self.one_time_keyboard = Some(true);
+        self
+    }
+
+    pub fn placeholder(mut self, placeholder: &str) -> Self {
+        self.input_field_placeholder = Some(placeholder.to_string());
+        self
+    }
+
+    pub fn selective(mut self) -> Self {
+        self.selective = Some(true);
+        self
+    }
+}
+
+/// Hides the custom keyboard and shows the system one.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReplyKeyboardRemove {
+    /// Always true
+    pub remove_keyboard: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub selective: Option<bool>,
+}
+
+impl ReplyKeyboardRemove {
+    pub fn new() -> Self {
+        Self {
+            remove_keyboard: true,
+            selective: None,
+        }
+    }
+
+    pub fn selective(mut self) -> Self {
+        self.selective = Some(true);
+        self
+    }
+}
+
+impl Default for ReplyKeyboardRemove {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Opens a reply interface to the bot's message, as if the user selected
+/// "Reply", e.g. to ask for input step by step.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ForceReply {
+    /// Always true
+    pub force_reply: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub input_field_placeholder: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub selective: Option<bool>,
+}
+
+impl ForceReply {
+    pub fn new() -> Self {
+        Self {
+            force_reply: true,
+            input_field_placeholder: None,
+            selective: None,
+        }
+    }
+
+    pub fn placeholder(mut self, placeholder: &str) -> Self {
+        self.input_field_placeholder = Some(placeholder.to_string());
+        self
+    }
+
+    pub fn selective(mut self) -> Self {
+        self.selective = Some(true);
+        self
+    }
+}
+
+impl Default for ForceReply {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Markup sent along with a message.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ReplyMarkup {
+    Keyboard(ReplyKeyboardMarkup),
+    Remove(ReplyKeyboardRemove),
+    ForceReply(ForceReply),
+}
+
+impl From<ReplyKeyboardMarkup> for ReplyMarkup {
+    fn from(markup: ReplyKeyboardMarkup) -> Self {
+        ReplyMarkup::Keyboard(markup)
+    }
+}
+
+impl From<ReplyKeyboardRemove> for ReplyMarkup {
+    fn from(markup: ReplyKeyboardRemove) -> Self {
+        ReplyMarkup::Remove(markup)
+    }
+}
+
+impl From<ForceReply> for ReplyMarkup {
+    fn from(markup: ForceReply) -> Self {
+        ReplyMarkup::ForceReply(markup)
+    }
+}
+
+/// A phone number shared with a contact button.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Contact {
+    pub phone_number: String,
+    pub first_name: String,
+    pub last_name: Option<String>,
+    pub user_id: Option<i64>,
+    pub vcard: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Location {
+    pub latitude: f64,
+    pub longitude: f64,
+    /// Radius of uncertainty in meters
+    pub horizontal_accuracy: Option<f64>,
+    /// For how long a live location is updated, in seconds
+    pub live_period: Option<i64>,
+}
+
+/// A user picked with a `request_users` button.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SharedUser {
+    pub user_id: i64,
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+    pub username: Option<String>,
+    pub photo: Option<Vec<PhotoSize>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsersShared {
+    pub request_id: i32,
+    pub users: Vec<SharedUser>,
+}
+
+/// A chat picked with a `request_chat` button.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatShared {
+    pub request_id: i32,
+    pub chat_id: i64,
+    pub title: Option<String>,
+    pub username: Option<String>,
+    pub photo: Option<Vec<PhotoSize>>,
+}
+
+/// Data sent by a Web App opened with a `web_app` button.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebAppData {
+    pub data: String,
+    /// Text of the button that opened the Web App
+    pub button_text: String,
+}
@@ -0,0 +1,175 @@
+use serde::{Deserialize, Serialize};
+
+/// One size of a photo or a file/sticker thumbnail.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PhotoSize {
+    /// Identifier for this file, which can be used to download or reuse the file
+    pub file_id: String,
+    /// Unique identifier for this file, which is supposed to be the same over time and for different bots.
+    pub file_unique_id: String,
+    pub width: i64,
+    pub height: i64,
+    pub file_size: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Video {
+    pub file_id: String,
+    pub file_unique_id: String,
+    pub width: i64,
+    pub height: i64,
+    /// Duration of the video in seconds
+    pub duration: i64,
+    pub thumbnail: Option<PhotoSize>,
+    pub cover: Option<Vec<PhotoSize>>,
+    pub start_timestamp: Option<i64>,
+    pub file_name: Option<String>,
+    pub mime_type: Option<String>,
+    pub file_size: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Audio {
+    pub file_id: String,
+    pub file_unique_id: String,
+    /// Duration of the audio in seconds
+    pub duration: i64,
+    pub performer: Option<String>,
+    pub title: Option<String>,
+    pub file_name: Option<String>,
+    pub mime_type: Option<String>,
+    pub file_size: Option<i64>,
+    pub thumbnail: Option<PhotoSize>,
+}
+
+/// A general file, as opposed to photos, voice messages and audio files.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Document {
+    pub file_id: String,
+    pub file_unique_id: String,
+    pub thumbnail: Option<PhotoSize>,
+    pub file_name: Option<String>,
+    pub mime_type: Option<String>,
+    pub file_size: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Voice {
+    pub file_id: String,
+    pub file_unique_id: String,
+    /// Duration of the audio in seconds
+    pub duration: i64,
+    pub mime_type: Option<String>,
+    pub file_size: Option<i64>,
+}
+
+/// A round video message.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VideoNote {
+    pub file_id: String,
+    pub file_unique_id: String,
+    /// Video width and height (diameter of the video message)
+    pub length: i64,
+    /// Duration of the video in seconds
+    pub duration: i64,
+    pub thumbnail: Option<PhotoSize>,
+    pub file_size: Option<i64>,
+}
+
+/// A GIF or H.264/MPEG-4 AVC video without sound.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Animation {
+    pub file_id: String,
+    pub file_unique_id: String,
+    pub width: i64,
+    pub height: i64,
+    /// Duration of the video in seconds
+    pub duration: i64,
+    pub thumbnail: Option<PhotoSize>,
+    pub file_name: Option<String>,
+    pub mime_type: Option<String>,
+    pub file_size: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Sticker {
+    pub file_id: String,
+    pub file_unique_id: String,
+    /// Type of the sticker: “regular”, “mask” or “custom_emoji”
+    #[serde(rename = "type")]
+    pub sticker_type: String,
+    pub width: i64,
+    pub height: i64,
+    pub is_animated: bool,
+    pub is_video: bool,
+    pub thumbnail: Option<PhotoSize>,
+    pub emoji: Option<String>,
+    pub set_name: Option<String>,
+    pub custom_emoji_id: Option<String>,
+    pub needs_repainting: Option<bool>,
+    pub file_size: Option<i64>,
+}
+
+/// Media attached to a message.
+#[derive(Debug, Clone, Copy)]
+pub enum Media<'a> {
+    /// Available sizes of the photo, the largest last
+    Photo(&'a [PhotoSize]),
+    Video(&'a Video),
+    Audio(&'a Audio),
+    Document(&'a Document),
+    Voice(&'a Voice),
+    VideoNote(&'a VideoNote),
+    Animation(&'a Animation),
+    Sticker(&'a Sticker),
+}
+
+impl Media<'_> {
+    /// File ID to re-send the media with. For photos, the largest size.
+    pub fn file_id(&self) -> Option<&str> {
+        let file_id = match self {
+            Media::Photo(sizes) => &sizes.last()?.file_id,
+            Media::Video(video) => &video.file_id,
+            Media::Audio(audio) => &audio.file_id,
+            Media::Document(document) => &document.file_id,
+            Media::Voice(voice) => &voice.file_id,
+            Media::VideoNote(video_note) => &video_note.file_id,
+            Media::Animation(animation) => &animation.file_id,
+            Media::Sticker(sticker) => &sticker.file_id,
+        };
+        Some(file_id)
+    }
+
+    /// Name of the message field and the `send*` parameter, e.g. `video_note`.
+    pub fn field(&self) -> &'static str {
+        match self {
+            Media::Photo(_) => "photo",
+            Media::Video(_) => "video",
+            Media::Audio(_) => "audio",
+            Media::Document(_) => "document",
+            Media::Voice(_) => "voice",
+            Media::VideoNote(_) => "video_note",
+            Media::Animation(_) => "animation",
+            Media::Sticker(_) => "sticker",
+        }
+    }
+
+    /// API method that sends this kind of media, e.g. `sendVideoNote`.
+    pub fn send_method(&self) -> &'static str {
+        match self {
+            Media::Photo(_) => "sendPhoto",
+            Media::Video(_) => "sendVideo",
+            Media::Audio(_) => "sendAudio",
+            Media::Document(_) => "sendDocument",
+            Media::Voice(_) => "sendVoice",
+            Media::VideoNote(_) => "sendVideoNote",
+            Media::Animation(_) => "sendAnimation",
+            Media::Sticker(_) => "sendSticker",
+        }
+    }
+
+    /// Video notes and stickers cannot have a caption.
+    pub fn supports_caption(&self) -> bool {
+        !matches!(self, Media::VideoNote(_) | Media::Sticker(_))
+    }
+}
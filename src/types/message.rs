@@ -1,8 +1,14 @@
-use crate::types::{Chat, Entity, UserOption};
+use crate::types::{
+    Animation, Audio, Chat, ChatShared, Contact, Document, Entity, ForumTopicClosed,
+    ForumTopicCreated, ForumTopicEdited, ForumTopicEvent, ForumTopicReopened,
+    GeneralForumTopicHidden, GeneralForumTopicUnhidden, Invoice, Location, Media, PhotoSize, Poll,
+    RefundedPayment, Sticker, SuccessfulPayment, User, UserOption, UsersShared, Video, VideoNote,
+    Voice, WebAppData,
+};
 use serde::{Deserialize, Serialize};
 use std::fmt::Display;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Message {
     pub message_id: i64,
     pub message_thread_id: Option<i64>,
@@ -10,9 +16,10 @@ pub struct Message {
     pub from: UserOption,
     pub sender_chat: Option<serde_json::Value>,
     pub sender_boost_count: Option<i64>,
-    pub sender_business_bot: Option<serde_json::Value>,
+    pub sender_business_bot: Option<User>,
     pub date: i64,
-    pub business_connection_id: Option<serde_json::Value>,
+    /// Set for messages received from or sent on behalf of a connected business account
+    pub business_connection_id: Option<String>,
     pub chat: Chat,
     forward_origin: Option<serde_json::Value>,
     is_topic_message: Option<bool>,
@@ -35,26 +42,27 @@ pub struct Message {
     link_preview_options: Option<serde_json::Value>,
     suggested_post_info: Option<serde_json::Value>,
     effect_id: Option<String>,
-    animation: Option<serde_json::Value>,
-    audio: Option<serde_json::Value>,
-    document: Option<serde_json::Value>,
+    animation: Option<Animation>,
+    audio: Option<Audio>,
+    document: Option<Document>,
     paid_media: Option<serde_json::Value>,
-    photo: Option<serde_json::Value>,
-    sticker: Option<serde_json::Value>,
+    photo: Option<Vec<PhotoSize>>,
+    sticker: Option<Sticker>,
     story: Option<serde_json::Value>,
-    video: Option<serde_json::Value>,
-    video_note: Option<serde_json::Value>,
-    voice: Option<serde_json::Value>,
+    video: Option<Video>,
+    video_note: Option<VideoNote>,
+    voice: Option<Voice>,
     caption: Option<String>,
-    caption_entities: Option<serde_json::Value>,
+    caption_entities: Option<Vec<Entity>>,
     show_caption_above_media: Option<bool>,
     has_media_spoiler: Option<bool>,
     checklist: Option<serde_json::Value>,
+    contact: Option<Contact>,
     dice: Option<serde_json::Value>,
     game: Option<serde_json::Value>,
-    poll: Option<serde_json::Value>,
+    poll: Option<Poll>,
     venue: Option<serde_json::Value>,
-    location: Option<serde_json::Value>,
+    location: Option<Location>,
     new_chat_members: Option<serde_json::Value>,
     left_chat_member: Option<serde_json::Value>,
     new_chat_title: Option<String>,
@@ -67,11 +75,11 @@ pub struct Message {
     migrate_to_chat_id: Option<i64>,
     migrate_from_chat_id: Option<i64>,
     pinned_message: Option<serde_json::Value>,
-    invoice: Option<serde_json::Value>,
-    successful_payment: Option<serde_json::Value>,
-    refunded_payment: Option<serde_json::Value>,
-    users_shared: Option<serde_json::Value>,
-    chat_shared: Option<serde_json::Value>,
+    invoice: Option<Invoice>,
+    successful_payment: Option<SuccessfulPayment>,
+    refunded_payment: Option<RefundedPayment>,
+    users_shared: Option<UsersShared>,
+    chat_shared: Option<ChatShared>,
     gift: Option<serde_json::Value>,
     unique_gift: Option<serde_json::Value>,
     connected_website: Option<String>,
@@ -86,13 +94,13 @@ pub struct Message {
 
     directed_message_price_changed: Option<serde_json::Value>,
 
-    forum_topic_created: Option<serde_json::Value>,
-    forum_topic_edited: Option<serde_json::Value>,
-    forum_topic_closed: Option<serde_json::Value>,
-    forum_topic_reopened: Option<serde_json::Value>,
+    forum_topic_created: Option<ForumTopicCreated>,
+    forum_topic_edited: Option<ForumTopicEdited>,
+    forum_topic_closed: Option<ForumTopicClosed>,
+    forum_topic_reopened: Option<ForumTopicReopened>,
 
-    general_forum_topic_hidden: Option<serde_json::Value>,
-    general_forum_topic_unhidden: Option<serde_json::Value>,
+    general_forum_topic_hidden: Option<GeneralForumTopicHidden>,
+    general_forum_topic_unhidden: Option<GeneralForumTopicUnhidden>,
 
     giveaway_created: Option<serde_json::Value>,
     giveaway: Option<serde_json::Value>,
@@ -112,10 +120,168 @@ pub struct Message {
     video_chat_ended: Option<serde_json::Value>,
     video_chat_participants_invited: Option<serde_json::Value>,
 
-    web_app_data: Option<serde_json::Value>,
+    web_app_data: Option<WebAppData>,
     reply_markup: Option<serde_json::Value>,
 }
 
+impl Message {
+    pub fn animation(&self) -> Option<&Animation> {
+        self.animation.as_ref()
+    }
+
+    pub fn audio(&self) -> Option<&Audio> {
+        self.audio.as_ref()
+    }
+
+    pub fn document(&self) -> Option<&Document> {
+        self.document.as_ref()
+    }
+
+    /// Available sizes of the photo, the largest last.
+    pub fn photo(&self) -> Option<&[PhotoSize]> {
+        self.photo.as_deref()
+    }
+
+    pub fn is_topic_message(&self) -> bool {
+        self.is_topic_message.unwrap_or(false)
+    }
+
+    /// A business message sent by the owner of the business account, not by
+    /// their customer. Business chats are private chats with the customer,
+    /// so anyone else writing there is the account owner.
+    pub fn is_from_business_owner(&self) -> bool {
+        self.business_connection_id.is_some()
+            && self
+                .from
+                .0
+                .as_ref()
+                .is_some_and(|user| user.id != self.chat.id())
+    }
+
+    /// Thread to send replies to: the forum topic the message was sent in.
+    /// `None` for the General topic and chats without topics.
+    pub fn reply_thread_id(&self) -> Option<i64> {
+        self.message_thread_id.filter(|_| self.is_topic_message())
+    }
+
+    /// The forum service message this message carries, if any.
+    pub fn forum_topic_event(&self) -> Option<ForumTopicEvent<'_>> {
+        if let Some(created) = &self.forum_topic_created {
+            return Some(ForumTopicEvent::Created(created));
+        }
+        if let Some(edited) = &self.forum_topic_edited {
+            return Some(ForumTopicEvent::Edited(edited));
+        }
+        if self.forum_topic_closed.is_some() {
+            return Some(ForumTopicEvent::Closed);
+        }
+        if self.forum_topic_reopened.is_some() {
+            return Some(ForumTopicEvent::Reopened);
+        }
+        if self.general_forum_topic_hidden.is_some() {
+            return Some(ForumTopicEvent::GeneralHidden);
+        }
+        if self.general_forum_topic_unhidden.is_some() {
+            return Some(ForumTopicEvent::GeneralUnhidden);
+        }
+        None
+    }
+
+    pub fn invoice(&self) -> Option<&Invoice> {
+        self.invoice.as_ref()
+    }
+
+    pub fn successful_payment(&self) -> Option<&SuccessfulPayment> {
+        self.successful_payment.as_ref()
+    }
+
+    pub fn refunded_payment(&self) -> Option<&RefundedPayment> {
+        self.refunded_payment.as_ref()
+    }
+
+    pub fn poll(&self) -> Option<&Poll> {
+        self.poll.as_ref()
+    }
+
+    pub fn contact(&self) -> Option<&Contact> {
+        self.contact.as_ref()
+    }
+
+    pub fn location(&self) -> Option<&Location> {
+        self.location.as_ref()
+    }
+
+    /// Users picked with a `request_users` keyboard button.
+    pub fn users_shared(&self) -> Option<&UsersShared> {
+        self.users_shared.as_ref()
+    }
+
+    /// Chat picked with a `request_chat` keyboard button.
+    pub fn chat_shared(&self) -> Option<&ChatShared> {
+        self.chat_shared.as_ref()
+    }
+
+    pub fn web_app_data(&self) -> Option<&WebAppData> {
+        self.web_app_data.as_ref()
+    }
+
+    pub fn sticker(&self) -> Option<&Sticker> {
+        self.sticker.as_ref()
+    }
+
+    pub fn video(&self) -> Option<&Video> {
+        self.video.as_ref()
+    }
+
+    pub fn video_note(&self) -> Option<&VideoNote> {
+        self.video_note.as_ref()
+    }
+
+    pub fn voice(&self) -> Option<&Voice> {
+        self.voice.as_ref()
+    }
+
+    /// Caption of the photo, video, audio, document, voice or animation.
+    pub fn caption(&self) -> Option<&str> {
+        self.caption.as_deref()
+    }
+
+    /// Entities of the caption, e.g. bot commands in a photo caption.
+    pub fn caption_entities(&self) -> Option<&[Entity]> {
+        self.caption_entities.as_deref()
+    }
+
+    /// Text of the message, or the caption of a media message.
+    pub fn text_or_caption(&self) -> Option<&str> {
+        self.text.as_deref().or(self.caption.as_deref())
+    }
+
+    /// Entities of the text, or of the caption of a media message.
+    pub fn entities_or_caption_entities(&self) -> Option<&[Entity]> {
+        match &self.text {
+            Some(_) => self.entities.as_deref(),
+            None => self.caption_entities.as_deref(),
+        }
+    }
+
+    /// Media attached to the message, if any.
+    pub fn media(&self) -> Option<Media<'_>> {
+        // An animation message also carries a document for backward compatibility
+        if let Some(animation) = &self.animation {
+            return Some(Media::Animation(animation));
+        }
+        self.photo
+            .as_deref()
+            .map(Media::Photo)
+            .or(self.video.as_ref().map(Media::Video))
+            .or(self.audio.as_ref().map(Media::Audio))
+            .or(self.document.as_ref().map(Media::Document))
+            .or(self.voice.as_ref().map(Media::Voice))
+            .or(self.video_note.as_ref().map(Media::VideoNote))
+            .or(self.sticker.as_ref().map(Media::Sticker))
+    }
+}
+
 impl Display for Message {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let entitites = match &self.entities {
@@ -145,3 +311,9 @@ impl Display for Message {
         )
     }
 }
+
+/// Identifier of a message, returned by `copyMessage` and `forwardMessages`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MessageId {
+    pub message_id: i64,
+}
@@ -0,0 +1,126 @@
+use crate::types::User;
+use serde::{Deserialize, Serialize};
+
+/// Currency code of Telegram Stars.
+pub const STARS: &str = "XTR";
+
+/// A portion of the price for goods or services. `amount` is in the smallest
+/// units of the currency, e.g. cents, or whole stars for `XTR`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LabeledPrice {
+    pub label: String,
+    pub amount: i64,
+}
+
+impl LabeledPrice {
+    pub fn new(label: impl Into<String>, amount: i64) -> Self {
+        Self {
+            label: label.into(),
+            amount,
+        }
+    }
+}
+
+/// Basic information about an invoice attached to a message.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Invoice {
+    pub title: String,
+    pub description: String,
+    pub start_parameter: String,
+    pub currency: String,
+    pub total_amount: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShippingAddress {
+    /// Two-letter ISO 3166-1 alpha-2 country code
+    pub country_code: String,
+    pub state: String,
+    pub city: String,
+    pub street_line1: String,
+    pub street_line2: String,
+    pub post_code: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OrderInfo {
+    pub name: Option<String>,
+    pub phone_number: Option<String>,
+    pub email: Option<String>,
+    pub shipping_address: Option<ShippingAddress>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShippingOption {
+    pub id: String,
+    pub title: String,
+    pub prices: Vec<LabeledPrice>,
+}
+
+/// Service message about a successful payment.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SuccessfulPayment {
+    pub currency: String,
+    pub total_amount: i64,
+    /// Bot-specified invoice payload
+    pub invoice_payload: String,
+    pub subscription_expiration_date: Option<i64>,
+    pub is_recurring: Option<bool>,
+    pub is_first_recurring: Option<bool>,
+    pub shipping_option_id: Option<String>,
+    pub order_info: Option<OrderInfo>,
+    /// Needed to refund the payment with `refundStarPayment`
+    pub telegram_payment_charge_id: String,
+    pub provider_payment_charge_id: String,
+}
+
+/// Service message about a refunded payment.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RefundedPayment {
+    pub currency: String,
+    pub total_amount: i64,
+    pub invoice_payload: String,
+    pub telegram_payment_charge_id: String,
+    pub provider_payment_charge_id: Option<String>,
+}
+
+/// Incoming shipping query, only for invoices with flexible price.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShippingQuery {
+    pub id: String,
+    pub from: User,
+    pub invoice_payload: String,
+    pub shipping_address: ShippingAddress,
+}
+
+/// Incoming pre-checkout query. Must be answered within 10 seconds.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PreCheckoutQuery {
+    pub id: String,
+    pub from: User,
+    pub currency: String,
+    pub total_amount: i64,
+    pub invoice_payload: String,
+    pub shipping_option_id: Option<String>,
+    pub order_info: Option<OrderInfo>,
+}
+
+/// A Telegram Star transaction of the bot.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StarTransaction {
+    /// Unique identifier of the transaction; coincides with `telegram_payment_charge_id` for payments
+    pub id: String,
+    /// Integer amount of Telegram Stars transferred by the transaction
+    pub amount: i64,
+    pub nanostar_amount: Option<i64>,
+    pub date: i64,
+    /// Source of an incoming transaction
+    pub source: Option<serde_json::Value>,
+    /// Receiver of an outgoing transaction
+    pub receiver: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StarTransactions {
+    pub transactions: Vec<StarTransaction>,
+}
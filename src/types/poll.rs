@@ -0,0 +1,53 @@
+use crate::types::{Entity, User};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PollType {
+    Regular,
+    Quiz,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PollOption {
+    pub text: String,
+    pub text_entities: Option<Vec<Entity>>,
+    /// Number of users that voted for this option
+    pub voter_count: i64,
+}
+
+/// A poll, as attached to a message or sent in a `poll` update.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Poll {
+    pub id: String,
+    pub question: String,
+    pub question_entities: Option<Vec<Entity>>,
+    pub options: Vec<PollOption>,
+    pub total_voter_count: i64,
+    pub is_closed: bool,
+    pub is_anonymous: bool,
+    #[serde(rename = "type")]
+    pub poll_type: PollType,
+    pub allows_multiple_answers: bool,
+    /// 0-based identifier of the correct answer option. Available only for
+    /// quizzes sent by the bot or closed quizzes.
+    pub correct_option_id: Option<usize>,
+    pub explanation: Option<String>,
+    pub explanation_entities: Option<Vec<Entity>>,
+    /// Amount of time in seconds the poll will be active after creation
+    pub open_period: Option<i64>,
+    /// Point in time (Unix timestamp) when the poll will be automatically closed
+    pub close_date: Option<i64>,
+}
+
+/// An answer of a user in a non-anonymous poll.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PollAnswer {
+    pub poll_id: String,
+    /// The chat that changed the answer, if the voter is anonymous
+    pub voter_chat: Option<serde_json::Value>,
+    /// The user that changed the answer, if the voter isn't anonymous
+    pub user: Option<User>,
+    /// 0-based identifiers of chosen options. Empty if the vote was retracted.
+    pub option_ids: Vec<usize>,
+}
@@ -4,11 +4,13 @@ use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct User {
+    pub id: i64,
     pub is_bot: bool,
     pub first_name: String,
     pub last_name: Option<String>,
     pub username: Option<String>,
-    pub language_code: Option<serde_json::Value>,
+    /// IETF language tag of the user's language, e.g. `en` or `pt-BR`.
+    pub language_code: Option<String>,
     pub is_premium: Option<serde_json::Value>,
     pub added_to_attachment_menu: Option<serde_json::Value>,
     pub can_join_groups: Option<serde_json::Value>,
@@ -18,7 +20,7 @@ pub struct User {
     pub has_main_web_app: Option<serde_json::Value>,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct UserOption(pub Option<User>);
 
 impl Display for User {